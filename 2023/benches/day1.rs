@@ -0,0 +1,21 @@
+// cargo +nightly bench --bench day1
+#![feature(test)]
+
+extern crate test;
+
+use aoc::day1::{solver_part1, solver_part1_swar};
+use test::{black_box, Bencher};
+
+const INPUT: &[u8] = include_bytes!("../input/2023/day1.txt");
+
+#[bench]
+fn part1_scalar(b: &mut Bencher) {
+    b.bytes = INPUT.len() as u64;
+    b.iter(|| solver_part1(black_box(INPUT)));
+}
+
+#[bench]
+fn part1_swar(b: &mut Bencher) {
+    b.bytes = INPUT.len() as u64;
+    b.iter(|| solver_part1_swar(black_box(INPUT)));
+}
@@ -0,0 +1,48 @@
+// cargo +nightly bench --bench day3
+#![feature(test)]
+
+extern crate test;
+
+use aoc::{day3, gen};
+use test::{black_box, Bencher};
+
+fn schematic(size: u16) -> (u16, u16, Vec<day3::Entry>) {
+    day3::generator(&gen::Day3::default().with_size(size, size).generate(2023)).unwrap()
+}
+
+#[bench]
+fn part1_hierarchy_200(b: &mut Bencher) {
+    let schematic = schematic(200);
+    b.iter(|| day3::solver_part1(black_box(&schematic)));
+}
+
+#[bench]
+fn part1_graph_200(b: &mut Bencher) {
+    let schematic = schematic(200);
+    b.iter(|| day3::solver_part1_graph(black_box(&schematic)));
+}
+
+#[bench]
+fn part1_brute_200(b: &mut Bencher) {
+    let schematic = schematic(200);
+    b.iter(|| day3::solver_part1_brute(black_box(&schematic)));
+}
+
+#[bench]
+fn part1_hierarchy_1000(b: &mut Bencher) {
+    let schematic = schematic(1000);
+    b.iter(|| day3::solver_part1(black_box(&schematic)));
+}
+
+#[bench]
+fn part2_hierarchy_1000(b: &mut Bencher) {
+    let schematic = schematic(1000);
+    b.iter(|| day3::solver_part2(black_box(&schematic)));
+}
+
+#[bench]
+fn generator_1000(b: &mut Bencher) {
+    let input = gen::Day3::default().with_size(1000, 1000).generate(2023);
+    b.bytes = input.len() as u64;
+    b.iter(|| day3::generator(black_box(&input)));
+}
@@ -0,0 +1,37 @@
+// Writes a day's generator output as JSON to a file or stdout:
+//
+//     cargo run --features serde --example dump -- 3 input/2023/example3.txt
+//     cargo run --features serde --example dump -- 2 input/2023/day2.txt day2.json
+
+use std::{error::Error, fs, process::ExitCode};
+
+use aoc::dump;
+
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (day, input, output) = match args {
+        [day, input] => (day, input, None),
+        [day, input, output] => (day, input, Some(output)),
+        _ => return Err("usage: dump <day> <input> [output]".into()),
+    };
+
+    let input = fs::read(input)?;
+    match output {
+        Some(output) => dump::write(day.parse()?, &input, output),
+        None => {
+            print!("{}", dump::generated(day.parse()?, &input)?);
+            Ok(())
+        },
+    }
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        },
+    }
+}
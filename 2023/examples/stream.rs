@@ -0,0 +1,65 @@
+// Runs the streaming solvers over stdin, a file, or a generated stress input
+// that never has to fit in memory:
+//
+//     cargo run --release --example stream -- day1 2 < input/2023/day1.txt
+//     cargo run --release --example stream -- day2 1 input/2023/day2.txt
+//     cargo run --release --example stream -- day1 2 --repeat 4096
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    process::ExitCode,
+    time::Instant,
+};
+
+use aoc::{day1, day2, util::stream::Repeat};
+
+const DAY1: &[u8] = include_bytes!("../input/2023/day1.txt");
+const DAY2: &[u8] = include_bytes!("../input/2023/day2.txt");
+
+fn solve(day: &str, part: &str, reader: impl BufRead) -> Result<u32, Box<dyn Error>> {
+    Ok(match (day, part) {
+        ("day1", "1") => day1::solve_stream_part1(reader)?,
+        ("day1", "2") => day1::solve_stream_part2(reader)?,
+        ("day2", "1") => day2::solve_stream_part1(reader)?,
+        ("day2", "2") => day2::solve_stream_part2(reader)?,
+        _ => return Err(format!("no streaming solver for {day} part {part}").into()),
+    })
+}
+
+fn run(args: &[String]) -> Result<u32, Box<dyn Error>> {
+    let [day, part, rest @ ..] = args else {
+        return Err("usage: stream <day1|day2> <1|2> [path | --repeat <MiB>]".into());
+    };
+
+    match rest {
+        [] => solve(day, part, io::stdin().lock()),
+        [flag, mebibytes] if flag == "--repeat" => {
+            // whole copies of the real input so every line stays valid
+            let template = if day == "day1" { DAY1 } else { DAY2 };
+            let copies = (mebibytes.parse::<u64>()? << 20).div_ceil(template.len() as u64);
+
+            solve(day, part, BufReader::new(Repeat::new(template, copies * template.len() as u64)))
+        },
+        [path] => solve(day, part, BufReader::new(File::open(path)?)),
+        _ => Err("too many arguments".into()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let start = Instant::now();
+
+    match run(&args) {
+        Ok(answer) => {
+            println!("{answer}");
+            eprintln!("took {:?}", start.elapsed());
+            ExitCode::SUCCESS
+        },
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        },
+    }
+}
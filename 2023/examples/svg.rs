@@ -0,0 +1,36 @@
+// Writes the day 3 audit rendering of a schematic:
+//
+//     cargo run --example svg -- input/2023/example3.txt example3.svg
+//
+// accepted numbers are green, rejected ones red, symbols blue, and the
+// neighbourhood each symbol accepts numbers from is shaded.
+
+use std::{error::Error, fs, process::ExitCode};
+
+use aoc::day3;
+
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let [input, output] = args else {
+        return Err("usage: svg <input> <output>".into());
+    };
+
+    let bytes = fs::read(input)?;
+    let (width, height, entries) = day3::generator(&bytes).map_err(|error| {
+        let (line, column) = error.line_col(&bytes);
+        format!("{input}:{line}:{column}: {error}")
+    })?;
+
+    Ok(day3::render_svg(width, height, &entries).write_to(output)?)
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        },
+    }
+}
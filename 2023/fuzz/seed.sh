@@ -0,0 +1,14 @@
+#!/bin/sh
+# Adds the full puzzle input from input/2023 to the committed seeds in
+# fuzz/corpus/<day> and starts libFuzzer on one day,
+# e.g. `./seed.sh day3 -max_total_time=60`. Needs cargo-fuzz and nightly.
+set -e
+
+cd "$(dirname "$0")"
+target="$1"
+shift
+
+mkdir -p "corpus/$target"
+cp ../input/2023/"$target".txt "corpus/$target/" 2>/dev/null || true
+
+cargo +nightly fuzz run "$target" "corpus/$target" -- "$@"
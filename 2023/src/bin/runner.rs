@@ -0,0 +1,281 @@
+// Project runner for things cargo-aoc does not do:
+//
+//     cargo run --release --bin runner -- compare 3          # both parts
+//     cargo run --release --bin runner -- compare 1 2 input/2023/day1.txt
+//     cargo run --release --bin runner -- gen 3 10000 42 > /tmp/day3-10k.txt
+//     cargo run --release --bin runner -- watch 3            # re-run on save
+//     AOC_SESSION=... cargo run --bin runner -- fetch 4      # into input/2023
+//
+// watch re-runs the day when its input or example changes, and rebuilds and
+// restarts when Cargo.toml or any .rs file under src does.
+
+use std::{
+    env,
+    error::Error,
+    fs,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    process::{self, Command, ExitCode},
+    thread,
+    time::Duration,
+};
+
+use aoc::{
+    gen,
+    input::fetch::{self, Client},
+    runner::{self, PARTS},
+    watch::{Run, Watcher},
+};
+
+const USAGE: &str = "usage: runner compare <day> [part] [input]
+       runner gen <day> <size> [seed]
+       runner watch <day> [interval_ms]
+       runner fetch <day>...";
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+// every variant of the chosen parts; fails if any of them disagree
+fn compare(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (day, part, path) = match args {
+        [day] => (day.parse()?, None, None),
+        [day, part] => (day.parse()?, Some(part.parse()?), None),
+        [day, part, path] => (day.parse()?, Some(part.parse()?), Some(PathBuf::from(path))),
+        _ => return Err(USAGE.into()),
+    };
+
+    let input = match path {
+        Some(path) => fs::read(path)?,
+        None => Client::from_env().fetch(day)?,
+    };
+    let parts = PARTS.iter().filter(|&&(d, p)| d == day && part.is_none_or(|part| part == p));
+
+    let mut found = false;
+    for &(day, part) in parts {
+        found = true;
+        print!("{}", runner::compare(day, part, &input)?);
+    }
+
+    if found {
+        Ok(())
+    } else {
+        Err(runner::CompareError::UnknownPart { day, part: part.unwrap_or(0) }.into())
+    }
+}
+
+// a random input on stdout; size is lines for day 1, games for day 2 and
+// the grid side for day 3
+fn generate(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (day, size, seed) = match args {
+        [day, size] => (day.parse()?, size.parse()?, 0),
+        [day, size, seed] => (day.parse()?, size.parse()?, seed.parse()?),
+        _ => return Err(USAGE.into()),
+    };
+
+    let output = BufWriter::new(io::stdout().lock());
+    match day {
+        1 => gen::Day1::default().with_lines(size).write(seed, output)?,
+        2 => gen::Day2::default().with_games(size).write(seed, output)?,
+        3 => {
+            let side = u16::try_from(size).map_err(|_| "day 3 grids are at most 65535 wide")?;
+            gen::Day3::default().with_size(side, side).write(seed, output)?
+        },
+        _ => return Err(format!("no input generator for day {day}").into()),
+    }
+
+    Ok(())
+}
+
+// the real input and the example, whichever exist
+fn watched_inputs(day: u32) -> Vec<PathBuf> {
+    vec![Client::from_env().path(day), manifest_dir().join(format!("input/2023/example{day}.txt"))]
+}
+
+// everything the runner is built from: Cargo.toml and every .rs file under
+// src, as they are when watching starts
+fn watched_sources() -> Vec<PathBuf> {
+    fn rust_files(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+            if path.is_dir() {
+                rust_files(&path, files);
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                files.push(path);
+            }
+        }
+    }
+
+    let mut sources = vec![manifest_dir().join("Cargo.toml")];
+    rust_files(&manifest_dir().join("src"), &mut sources);
+    sources[1..].sort();
+
+    sources
+}
+
+fn read_inputs(paths: &[PathBuf]) -> Vec<(String, Vec<u8>)> {
+    paths
+        .iter()
+        .filter_map(|path| Some((path.file_stem()?.to_string_lossy().into_owned(), fs::read(path).ok()?)))
+        .collect()
+}
+
+// the features this binary was built with, so a rebuild keeps them
+fn features() -> Vec<&'static str> {
+    [("fuzzing", cfg!(feature = "fuzzing")), ("serde", cfg!(feature = "serde")), ("tui", cfg!(feature = "tui"))]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect()
+}
+
+// rebuilds this binary with the same profile and features; cargo is the one
+// running us when there is one
+fn rebuild() -> io::Result<bool> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(cargo);
+    command.current_dir(manifest_dir()).args(["build", "--bin", "runner"]);
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    let features = features();
+    if !features.is_empty() {
+        command.args(["--features", &features.join(",")]);
+    }
+
+    Ok(command.status()?.success())
+}
+
+// replaces this process with the freshly built binary
+#[cfg(unix)]
+fn restart(binary: &Path, args: &[String]) -> Result<(), Box<dyn Error>> {
+    use std::os::unix::process::CommandExt;
+
+    Err(Command::new(binary).args(args).exec().into())
+}
+
+#[cfg(not(unix))]
+fn restart(binary: &Path, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let status = Command::new(binary).args(args).status()?;
+    process::exit(status.code().unwrap_or(1))
+}
+
+// runs the default solvers of a day on its inputs, then again whenever an
+// input changes; source changes rebuild and restart the runner, handing the
+// last run over through a file so the report still compares against it
+fn watch(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (day, interval, state) = match args {
+        [day] => (day.parse()?, 500, None),
+        [day, interval] => (day.parse()?, interval.parse()?, None),
+        [day, interval, flag, path] if flag == "--previous" => (day.parse()?, interval.parse()?, Some(PathBuf::from(path))),
+        _ => return Err(USAGE.into()),
+    };
+    if !PARTS.iter().any(|&(d, _)| d == day) {
+        return Err(runner::CompareError::UnknownPart { day, part: 0 }.into());
+    }
+
+    // the examples are still worth watching without the real input
+    if let Err(error) = Client::from_env().fetch(day) {
+        eprintln!("{error}");
+    }
+
+    // resolved up front, once the build replaces the binary this process's
+    // own path reads as deleted
+    let binary = env::current_exe()?;
+    let inputs = watched_inputs(day);
+    let mut watcher = Watcher::new(inputs.iter().cloned().chain(watched_sources()));
+    let mut previous = state.and_then(|path| {
+        let text = fs::read_to_string(&path).ok()?;
+        let _ = fs::remove_file(path);
+        Run::from_text(&text)
+    });
+
+    let watched = watcher.paths().map(|path| path.strip_prefix(manifest_dir()).unwrap_or(path).display().to_string());
+    eprintln!("watching {}", watched.collect::<Vec<_>>().join(" "));
+
+    loop {
+        let run = Run::execute(day, &read_inputs(&inputs));
+        print!("{}", run.report(previous.as_ref()));
+        println!();
+        previous = Some(run);
+
+        // wait for an input change, restarting on the way if sources change
+        loop {
+            thread::sleep(Duration::from_millis(interval));
+            let changed = watcher.changed();
+
+            if changed.iter().any(|path| !inputs.contains(path)) {
+                eprintln!("sources changed, rebuilding");
+                if !rebuild()? {
+                    eprintln!("build failed, waiting for changes");
+                    // the old binary can still run a changed input
+                    if changed.iter().any(|path| inputs.contains(path)) {
+                        break;
+                    }
+                    continue;
+                }
+
+                // not under target/, which may be elsewhere or not exist yet;
+                // exec keeps the pid, so it is unique to this watch
+                let state = env::temp_dir().join(format!("aoc-watch-day{day}-{}.tsv", process::id()));
+                fs::write(&state, previous.as_ref().map(Run::to_text).unwrap_or_default())?;
+                return restart(&binary, &[
+                    "watch".to_string(),
+                    day.to_string(),
+                    interval.to_string(),
+                    "--previous".to_string(),
+                    state.display().to_string(),
+                ]);
+            }
+            if !changed.is_empty() {
+                break;
+            }
+        }
+    }
+}
+
+// downloads the inputs of the given days unless they are cached already, and
+// records the checksums of cached ones that have none
+fn download(args: &[String]) -> Result<(), Box<dyn Error>> {
+    if args.is_empty() {
+        return Err(USAGE.into());
+    }
+
+    let client = Client::from_env();
+    for day in args {
+        let day = day.parse()?;
+        let cached = client.is_cached(day);
+        let input = client.record(day)?;
+
+        println!(
+            "{}  {}{}",
+            fetch::checksum(&input),
+            client.path(day).display(),
+            if cached { "  (cached)" } else { "" },
+        );
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let result = match args.split_first() {
+        Some((command, rest)) if command == "compare" => compare(rest),
+        Some((command, rest)) if command == "gen" => generate(rest),
+        Some((command, rest)) if command == "watch" => watch(rest),
+        Some((command, rest)) if command == "fetch" => download(rest),
+        _ => Err(USAGE.into()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        },
+    }
+}
@@ -0,0 +1,158 @@
+// Interactive explorer for the day 3 grid:
+//
+//     cargo run --features tui --bin tui -- [input/2023/day3.txt]
+//
+// arrows or hjkl move the cursor, HJKL move by ten, PageUp/PageDown by a
+// screen, Tab jumps to the next entry, q or Esc quits. The entry under the
+// cursor is green, its expanded area yellow and the hierarchy leaves that
+// intersect that area cyan.
+
+use std::{
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use aoc::{
+    explore::{Explorer, Highlight},
+    util::Point,
+};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    queue,
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, ClearType},
+};
+
+// rows below the grid for the status and key help
+const STATUS_ROWS: u16 = 2;
+
+fn colours(highlight: Highlight, byte: u8) -> (Color, Color) {
+    match highlight {
+        Highlight::Selected => (Color::Black, Color::Green),
+        Highlight::Leaf => (Color::Black, Color::Cyan),
+        Highlight::Neighbourhood => (Color::Black, Color::Yellow),
+        Highlight::None if byte == b'.' => (Color::DarkGrey, Color::Reset),
+        Highlight::None if byte.is_ascii_digit() => (Color::Reset, Color::Reset),
+        Highlight::None => (Color::Blue, Color::Reset),
+    }
+}
+
+fn draw(out: &mut impl Write, explorer: &mut Explorer) -> io::Result<()> {
+    let (columns, rows) = terminal::size()?;
+    let view_height = rows.saturating_sub(STATUS_ROWS).max(1);
+    explorer.follow(columns, view_height);
+
+    let scroll = explorer.scroll();
+    let visible_width = columns.min(explorer.width() - scroll.x);
+    let visible_height = view_height.min(explorer.height() - scroll.y);
+
+    queue!(out, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+
+    for y in 0..visible_height {
+        queue!(out, cursor::MoveTo(0, y))?;
+
+        for x in 0..visible_width {
+            let cell = Point::new(scroll.x + x, scroll.y + y);
+            let byte = explorer.byte(cell);
+            let (foreground, background) = colours(explorer.highlight(cell), byte);
+
+            if cell == explorer.cursor() {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(
+                out,
+                SetForegroundColor(foreground),
+                SetBackgroundColor(background),
+                Print(byte as char),
+                SetAttribute(Attribute::Reset),
+                ResetColor,
+            )?;
+        }
+    }
+
+    let status = explorer.status();
+    let help = "arrows/hjkl move  HJKL x10  PgUp/PgDn page  Tab next entry  q quit";
+    queue!(
+        out,
+        cursor::MoveTo(0, view_height),
+        Print(&status[..status.len().min(columns as usize)]),
+        cursor::MoveTo(0, view_height + 1),
+        SetForegroundColor(Color::DarkGrey),
+        Print(&help[..help.len().min(columns as usize)]),
+        ResetColor,
+    )?;
+
+    out.flush()
+}
+
+fn run(out: &mut impl Write, explorer: &mut Explorer) -> io::Result<()> {
+    loop {
+        draw(out, explorer)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+
+        let page = terminal::size()?.1.saturating_sub(STATUS_ROWS).max(1) as i32;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Left | KeyCode::Char('h') => explorer.move_cursor(-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => explorer.move_cursor(1, 0),
+            KeyCode::Up | KeyCode::Char('k') => explorer.move_cursor(0, -1),
+            KeyCode::Down | KeyCode::Char('j') => explorer.move_cursor(0, 1),
+            KeyCode::Char('H') => explorer.move_cursor(-10, 0),
+            KeyCode::Char('L') => explorer.move_cursor(10, 0),
+            KeyCode::Char('K') => explorer.move_cursor(0, -10),
+            KeyCode::Char('J') => explorer.move_cursor(0, 10),
+            KeyCode::PageUp => explorer.move_cursor(0, -page),
+            KeyCode::PageDown => explorer.move_cursor(0, page),
+            KeyCode::Home => explorer.move_to(Point::new(0, explorer.cursor().y)),
+            KeyCode::End => explorer.move_to(Point::new(explorer.width() - 1, explorer.cursor().y)),
+            KeyCode::Tab => explorer.next_entry(),
+            _ => (),
+        }
+    }
+}
+
+fn explore(path: PathBuf) -> Result<(), Box<dyn Error>> {
+    let input = fs::read(&path)?;
+    let mut explorer = Explorer::new(&input).map_err(|error| {
+        let (line, column) = error.line_col(&input);
+        format!("{}:{line}:{column}: {error}", path.display())
+    })?;
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    queue!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let result = run(&mut out, &mut explorer);
+
+    // restore the terminal even if drawing failed
+    queue!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    out.flush()?;
+    terminal::disable_raw_mode()?;
+
+    Ok(result?)
+}
+
+fn main() -> ExitCode {
+    let path = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("input/2023/day3.txt"));
+
+    match explore(path) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        },
+    }
+}
@@ -1,72 +1,272 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, error::Error, fmt::Display, io::BufRead, ops::Range};
 
 use aoc_runner_derive::aoc;
 
+use crate::util::{json_escape, stream::{for_each_line, StreamError}, OverflowError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationError {
+    // 1-based line number
+    MissingDigit { line: usize },
+    Overflow,
+}
+
+impl Display for CalibrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingDigit { line } => write!(f, "no digit on line {line}"),
+            Self::Overflow => write!(f, "{}", OverflowError),
+        }
+    }
+}
+
+impl Error for CalibrationError {}
+
+impl From<OverflowError> for CalibrationError {
+    fn from(_: OverflowError) -> Self {
+        Self::Overflow
+    }
+}
+
+// running sum of the per-line values, empty lines are skipped; shared by the
+// slice and stream solvers so both number lines the same way
+#[derive(Default)]
+struct Calibration {
+    lines: usize,
+    total: u32,
+}
+
+impl Calibration {
+    fn add_line(&mut self, line: &[u8], value: impl Fn(&[u8]) -> Option<u32>) -> Result<(), CalibrationError> {
+        self.lines += 1;
+        if line.is_empty() {
+            return Ok(());
+        }
+
+        let value = value(line).ok_or(CalibrationError::MissingDigit { line: self.lines })?;
+        self.total = self.total.checked_add(value).ok_or(OverflowError)?;
+        Ok(())
+    }
+}
+
+fn calibrate(input: &[u8], value: impl Fn(&[u8]) -> Option<u32>) -> Result<u32, CalibrationError> {
+    calibrate_lines(input.split(|&x| x == b'\n'), value)
+}
+
+fn calibrate_lines<'a>(
+    mut lines: impl Iterator<Item = &'a [u8]>,
+    value: impl Fn(&[u8]) -> Option<u32>,
+) -> Result<u32, CalibrationError> {
+    let mut calibration = Calibration::default();
+
+    lines.try_for_each(|line| calibration.add_line(line, &value))?;
+    Ok(calibration.total)
+}
+
+// like `calibrate`, one line in memory at a time
+fn calibrate_stream<R: BufRead>(reader: R, value: impl Fn(&[u8]) -> Option<u32>) -> Result<u32, StreamError<CalibrationError>> {
+    let mut calibration = Calibration::default();
+
+    for_each_line(reader, |_, line| calibration.add_line(line, &value))?;
+    Ok(calibration.total)
+}
+
+fn line_value_part1(x: &[u8]) -> Option<u32> {
+    let i = x
+        .iter()
+        .copied()
+        .position(|x| x.is_ascii_digit())?;
+    let first = x[i];
+    let last = x
+        .iter()
+        .copied()
+        .skip(i)
+        .rfind(u8::is_ascii_digit)?;
+
+    Some(((first - b'0') * 10 + (last - b'0')) as u32)
+}
+
 #[aoc(day1, part1)]
-pub fn solver_part1(input: &[u8]) -> u32 {
-    input
-        .split(|&x| x == b'\n')
-        .map(|x| {
-            let i = x
-                .iter()
-                .copied()
-                .position(|x| b'0' <= x && x <= b'9')
-                .unwrap();
-            let first = x[i];
-            let last = x
-                .iter()
-                .copied()
-                .skip(i)
-                .filter(|&x| b'0' <= x && x <= b'9')
-                .last()
-                .unwrap();
-
-            ((first - b'0') * 10 + (last - b'0')) as u32
-        })
-        .sum()
+pub fn solver_part1(input: &[u8]) -> Result<u32, CalibrationError> {
+    calibrate(input, line_value_part1)
 }
 
-// words: one, two, three, four, five, six, seven, eight, nine
+pub fn solve_stream_part1<R: BufRead>(reader: R) -> Result<u32, StreamError<CalibrationError>> {
+    calibrate_stream(reader, line_value_part1)
+}
 
-struct PrefixTree {
+// the same scan eight bytes at a time, SWAR on u64 words
+const LANES: u64 = 0x0101_0101_0101_0101;
+
+// high bit set in every byte of `word` that is an ascii digit; exact per
+// byte since nothing carries between lanes
+fn digit_mask(word: u64) -> u64 {
+    // digits become 0..=9, everything else is >= 10
+    let x = word ^ (LANES * b'0' as u64);
+    // 7 low bits + 118 sets the high bit iff they are >= 10, and stays < 256
+    let at_least_ten = (x & (LANES * 0x7f)) + LANES * (0x80 - 10);
+
+    !(at_least_ten | x) & (LANES * 0x80)
+}
+
+// high bit set in every byte of `word` equal to `byte`, exact like
+// `digit_mask`
+fn byte_mask(word: u64, byte: u8) -> u64 {
+    // matching bytes become 0
+    let x = word ^ (LANES * byte as u64);
+    // 7 low bits + 127 sets the high bit iff they are non-zero
+    let nonzero = (x & (LANES * 0x7f)) + LANES * 0x7f;
+
+    !(nonzero | x) & (LANES * 0x80)
+}
+
+fn newline_position_swar(x: &[u8]) -> Option<usize> {
+    let chunks = x.chunks_exact(8);
+    let rest = chunks.remainder();
+
+    for (idx, chunk) in chunks.enumerate() {
+        let mask = byte_mask(u64::from_le_bytes(chunk.try_into().unwrap()), b'\n');
+        if mask != 0 {
+            return Some(idx * 8 + mask.trailing_zeros() as usize / 8);
+        }
+    }
+
+    rest.iter().position(|&x| x == b'\n').map(|idx| x.len() - rest.len() + idx)
+}
+
+// the pieces of `input.split(|&x| x == b'\n')`, found a word at a time
+struct LinesSwar<'a> {
+    rest: Option<&'a [u8]>,
+}
+
+impl<'a> LinesSwar<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self { rest: Some(input) }
+    }
+}
+
+impl<'a> Iterator for LinesSwar<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+
+        match newline_position_swar(rest) {
+            Some(idx) => {
+                self.rest = Some(&rest[idx + 1..]);
+                Some(&rest[..idx])
+            },
+            None => self.rest.take(),
+        }
+    }
+}
+
+fn first_digit_swar(x: &[u8]) -> Option<u8> {
+    let chunks = x.chunks_exact(8);
+    let rest = chunks.remainder();
+
+    for chunk in chunks {
+        let mask = digit_mask(u64::from_le_bytes(chunk.try_into().unwrap()));
+        if mask != 0 {
+            return Some(chunk[mask.trailing_zeros() as usize / 8]);
+        }
+    }
+
+    rest.iter().copied().find(u8::is_ascii_digit)
+}
+
+fn last_digit_swar(x: &[u8]) -> Option<u8> {
+    let chunks = x.rchunks_exact(8);
+    let rest = chunks.remainder();
+
+    for chunk in chunks {
+        let mask = digit_mask(u64::from_le_bytes(chunk.try_into().unwrap()));
+        if mask != 0 {
+            return Some(chunk[7 - mask.leading_zeros() as usize / 8]);
+        }
+    }
+
+    rest.iter().copied().rfind(u8::is_ascii_digit)
+}
+
+fn line_value_part1_swar(x: &[u8]) -> Option<u32> {
+    let first = first_digit_swar(x)?;
+    let last = last_digit_swar(x)?;
+
+    Some(((first - b'0') * 10 + (last - b'0')) as u32)
+}
+
+#[aoc(day1, part1, Swar)]
+pub fn solver_part1_swar(input: &[u8]) -> Result<u32, CalibrationError> {
+    calibrate_lines(LinesSwar::new(input), line_value_part1_swar)
+}
+
+pub(crate) const DIGIT_WORDS: [&[u8]; 9] = [b"one", b"two", b"three", b"four", b"five", b"six", b"seven", b"eight", b"nine"];
+
+struct Trie {
     value: Option<u32>,
-    trees: HashMap<u8, PrefixTree>,
+    trees: HashMap<u8, Trie>,
 }
 
-impl PrefixTree {
-    pub fn empty() -> Self {
+impl Trie {
+    fn empty() -> Self {
         Self {
             value: None,
             trees: HashMap::new(),
         }
     }
 
-    pub fn insert(&mut self, sequence: &[u8], value: u32) {
-        if sequence.is_empty() {
-            self.value = Some(value);
-        } else if self.trees.contains_key(&sequence[0]) {
-            self.trees.get_mut(&sequence[0])
-                .unwrap()
-                .insert(&sequence[1..], value);
-        } else {
-            let mut subtree = Self::empty();
-            subtree.insert(&sequence[1..], value);
-            self.trees.insert(sequence[0], subtree);
+    fn insert(&mut self, sequence: impl IntoIterator<Item = u8>, value: u32) {
+        let node = sequence
+            .into_iter()
+            .fold(self, |node, c| node.trees.entry(c).or_insert_with(Self::empty));
+        node.value = Some(value);
+    }
+
+    // (value, search length) of the shortest word `sequence` starts with
+    fn find(&self, sequence: impl IntoIterator<Item = u8>) -> Option<(u32, usize)> {
+        let mut sequence = sequence.into_iter();
+        let mut node = self;
+        let mut length = 0;
+
+        loop {
+            if let Some(value) = node.value {
+                return Some((value, length));
+            }
+            node = node.trees.get(&sequence.next()?)?;
+            length += 1;
         }
     }
+}
+
+// every word is stored forwards for `find` and backwards for `find_last`
+struct PrefixTree {
+    forward: Trie,
+    reversed: Trie,
+}
+
+impl PrefixTree {
+    pub fn empty() -> Self {
+        Self {
+            forward: Trie::empty(),
+            reversed: Trie::empty(),
+        }
+    }
+
+    pub fn insert(&mut self, sequence: &[u8], value: u32) {
+        self.forward.insert(sequence.iter().copied(), value);
+        self.reversed.insert(sequence.iter().rev().copied(), value);
+    }
 
     pub fn create() -> Self {
         let mut tree = Self::empty();
 
-        [
-            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-        ]
-        .into_iter()
-        .enumerate()
-        .for_each(|(idx, word)| tree.insert(word.as_bytes(), idx as u32 + 1));
+        DIGIT_WORDS
+            .into_iter()
+            .enumerate()
+            .for_each(|(idx, word)| tree.insert(word, idx as u32 + 1));
 
         (b'1'..=b'9')
-            .into_iter()
             .enumerate()
             .for_each(|(idx, word)| tree.insert(&[word], idx as u32 + 1));
 
@@ -75,73 +275,366 @@ impl PrefixTree {
 
     // (value, search length)
     pub fn find(&self, sequence: &[u8]) -> Option<(u32, usize)> {
-        if let Some(value) = self.value {
-            Some((value, 0))
-        } else if let Some((value, length)) = sequence
-            .get(0)
-            .and_then(|c| self.trees.get(c))
-            .and_then(|t| t.find(&sequence[1..]))
-        {
-            Some((value, length + 1))
-        } else {
-            None
-        }
+        self.forward.find(sequence.iter().copied())
+    }
+
+    // (value, span) of the match starting furthest left in `line`
+    pub fn find_first(&self, line: &[u8]) -> Option<Found> {
+        (0..line.len()).find_map(|start| {
+            self.find(&line[start..])
+                .map(|(value, length)| (value, start..start + length))
+        })
     }
+
+    // (value, span) of the match ending furthest right in `line`, scanning
+    // backwards from the end so overlaps like "twone" resolve to the later word
+    pub fn find_last(&self, line: &[u8]) -> Option<Found> {
+        (1..=line.len()).rev().find_map(|end| {
+            self.reversed
+                .find(line[..end].iter().rev().copied())
+                .map(|(value, length)| (value, end - length..end))
+        })
+    }
+}
+
+fn line_value_part2(tree: &PrefixTree, x: &[u8]) -> Option<u32> {
+    let (first, _) = tree.find_first(x)?;
+    let (last, _) = tree.find_last(x)?;
+
+    Some(first * 10 + last)
 }
 
 #[aoc(day1, part2)]
-pub fn solver_part2(input: &[u8]) -> u32 {
+pub fn solver_part2(input: &[u8]) -> Result<u32, CalibrationError> {
     let tree = PrefixTree::create();
 
+    calibrate(input, |line| line_value_part2(&tree, line))
+}
 
-    input
-        .split(|&x| x == b'\n')
-        .enumerate()
-        .map(|(_idx, x)| {
-            // println!("line {} ({})", _idx + 1, std::str::from_utf8(x).unwrap());
-            let mut first = 0;
-            let mut last = 0;
-
-            let mut i = 0;
-            while i < x.len() {
-                if let Some((digit, _length)) = tree.find(&x[i..]) {
-                    first = digit;
-                    // println!(
-                    //     "first <- {} '{:}'{}{:?}",
-                    //     digit,
-                    //     std::str::from_utf8(&x[i..i + _length]).unwrap(),
-                    //     " ".repeat(6 - _length),
-                    //     (i+1, i+_length+1)
-                    // );
-                    break;
-                }
-                i += 1;
-            }
-            while i < x.len() {
-                if let Some((digit, _length)) = tree.find(&x[i..]) {
-                    // println!(
-                    //     "last  <- {} '{}'{}{:?}",
-                    //     digit,
-                    //     std::str::from_utf8(&x[i..i + _length]).unwrap(),
-                    //     " ".repeat(6 - _length),
-                    //     (i+1, i+_length+1)
-                    // );
-                    last = digit;
+// the digit or word `x` starts with, by comparing against every word
+fn digit_at(x: &[u8]) -> Option<u32> {
+    match x.first()? {
+        c @ b'1'..=b'9' => Some((c - b'0') as u32),
+        _ => DIGIT_WORDS.iter().position(|word| x.starts_with(word)).map(|idx| idx as u32 + 1),
+    }
+}
+
+fn line_value_part2_scan(x: &[u8]) -> Option<u32> {
+    let first = (0..x.len()).find_map(|i| digit_at(&x[i..]))?;
+    let last = (0..x.len()).rev().find_map(|i| digit_at(&x[i..]))?;
+
+    Some(first * 10 + last)
+}
+
+#[aoc(day1, part2, Scan)]
+pub fn solver_part2_scan(input: &[u8]) -> Result<u32, CalibrationError> {
+    calibrate(input, line_value_part2_scan)
+}
+
+pub fn solve_stream_part2<R: BufRead>(reader: R) -> Result<u32, StreamError<CalibrationError>> {
+    let tree = PrefixTree::create();
+
+    calibrate_stream(reader, |line| line_value_part2(&tree, line))
+}
+
+// (value, span) as returned by the prefix tree
+type Found = (u32, Range<usize>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitMatch {
+    pub value: u32,
+    // byte offsets into the line
+    pub span: Range<usize>,
+    pub text: String,
+}
+
+impl DigitMatch {
+    fn new(line: &[u8], (value, span): Found) -> Self {
+        let text = String::from_utf8_lossy(&line[span.clone()]).into_owned();
+        Self { value, span, text }
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            "{{\"value\":{},\"start\":{},\"end\":{},\"text\":\"{}\"}}",
+            self.value,
+            self.span.start,
+            self.span.end,
+            json_escape(&self.text)
+        )
+    }
+}
+
+// how the calibration value of one non-empty line came about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineExplanation {
+    // 1-based, counting empty lines like the solvers do
+    pub line: usize,
+    pub text: String,
+    // every match in the line, by start
+    pub matches: Vec<DigitMatch>,
+    pub first: Option<DigitMatch>,
+    pub last: Option<DigitMatch>,
+    pub value: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub lines: Vec<LineExplanation>,
+}
+
+impl Explanation {
+    fn build(input: &[u8], line_matches: impl Fn(&[u8]) -> Vec<Found>, chosen: impl Fn(&[u8]) -> Option<(Found, Found)>) -> Self {
+        let lines = input
+            .split(|&x| x == b'\n')
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(idx, line)| {
+                let (first, last) = chosen(line).unzip();
+                let value = first.as_ref().zip(last.as_ref()).map(|((first, _), (last, _))| first * 10 + last);
+
+                LineExplanation {
+                    line: idx + 1,
+                    text: String::from_utf8_lossy(line).into_owned(),
+                    matches: line_matches(line).into_iter().map(|found| DigitMatch::new(line, found)).collect(),
+                    first: first.map(|found| DigitMatch::new(line, found)),
+                    last: last.map(|found| DigitMatch::new(line, found)),
+                    value,
                 }
-                i += 1;
+            })
+            .collect();
+
+        Self { lines }
+    }
+
+    // the sum the solver would return, `None` if it would fail
+    pub fn total(&self) -> Option<u32> {
+        self.lines.iter().try_fold(0u32, |total, line| total.checked_add(line.value?))
+    }
+
+    // one block per line: the line with its value, then a row per match
+    pub fn to_text(&self) -> String {
+        let number_width = self.lines.last().map_or(1, |line| line.line.to_string().len());
+        let text_width = self.lines.iter()
+            .flat_map(|line| &line.matches)
+            .map(|found| found.text.chars().count())
+            .max()
+            .unwrap_or(0);
+        let span_width = self.lines.iter()
+            .flat_map(|line| &line.matches)
+            .map(|found| format!("{:?}", found.span).len())
+            .max()
+            .unwrap_or(0);
+
+        let mut text = String::new();
+        for line in &self.lines {
+            let value = line.value.map_or("-".to_string(), |value| value.to_string());
+            text.push_str(&format!("{:>number_width$}  {}  => {value}\n", line.line, line.text));
+
+            for found in &line.matches {
+                let role = match (line.first.as_ref() == Some(found), line.last.as_ref() == Some(found)) {
+                    (true, true) => "first last",
+                    (true, false) => "first",
+                    (false, true) => "last",
+                    (false, false) => "",
+                };
+                let row = format!(
+                    "{:number_width$}  {}  {:text_width$}  {:span_width$}  {role}",
+                    "",
+                    found.value,
+                    found.text,
+                    format!("{:?}", found.span)
+                );
+                text.push_str(row.trim_end());
+                text.push('\n');
             }
+        }
 
-            let result = first * 10 + last;
-            // println!("result: {result}\n");
-            result
-        })
-        .sum()
+        let total = self.total().map_or("-".to_string(), |total| total.to_string());
+        text.push_str(&format!("total: {total}\n"));
+        text
+    }
+
+    pub fn to_json(&self) -> String {
+        fn optional(value: Option<String>) -> String {
+            value.unwrap_or_else(|| "null".to_string())
+        }
+
+        let lines = self.lines
+            .iter()
+            .map(|line| {
+                format!(
+                    "{{\"line\":{},\"text\":\"{}\",\"matches\":[{}],\"first\":{},\"last\":{},\"value\":{}}}",
+                    line.line,
+                    json_escape(&line.text),
+                    line.matches.iter().map(DigitMatch::to_json).collect::<Vec<_>>().join(","),
+                    optional(line.first.as_ref().map(DigitMatch::to_json)),
+                    optional(line.last.as_ref().map(DigitMatch::to_json)),
+                    optional(line.value.map(|value| value.to_string())),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!("{{\"lines\":[{}],\"total\":{}}}", lines.join(","), optional(self.total().map(|total| total.to_string())))
+    }
+}
+
+// every ascii digit, the first and last of them are chosen
+pub fn explain_part1(input: &[u8]) -> Explanation {
+    let digits = |line: &[u8]| {
+        line.iter()
+            .enumerate()
+            .filter(|(_, c)| c.is_ascii_digit())
+            .map(|(idx, &c)| ((c - b'0') as u32, idx..idx + 1))
+            .collect::<Vec<_>>()
+    };
+
+    Explanation::build(input, digits, |line| {
+        let digits = digits(line);
+        digits.first().cloned().zip(digits.last().cloned())
+    })
+}
+
+// every digit or word starting at each byte, the first and last come from
+// the prefix tree exactly as in `solver_part2`
+pub fn explain_part2(input: &[u8]) -> Explanation {
+    let tree = PrefixTree::create();
+
+    Explanation::build(
+        input,
+        |line| {
+            (0..line.len())
+                .filter_map(|start| tree.find(&line[start..]).map(|(value, length)| (value, start..start + length)))
+                .collect()
+        },
+        |line| tree.find_first(line).zip(tree.find_last(line)),
+    )
 }
 
 #[cfg(test)]
 mod tests {
+    mod solvers {
+        use crate::day1::{solver_part1, solver_part2, CalibrationError};
+
+        #[test]
+        fn example_part1() {
+            assert_eq!(solver_part1(b"1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n"), Ok(142));
+        }
+
+        #[test]
+        fn example_part2() {
+            let input = b"two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen";
+
+            assert_eq!(solver_part2(input), Ok(281));
+        }
+
+        #[test]
+        fn missing_digit_is_an_error() {
+            assert_eq!(solver_part1(b"12\n\nabc"), Err(CalibrationError::MissingDigit { line: 3 }));
+            assert_eq!(solver_part2(b"one\nzzz"), Err(CalibrationError::MissingDigit { line: 2 }));
+        }
+    }
+
+    mod explain {
+        use crate::day1::{explain_part1, explain_part2, solver_part1, solver_part2, DigitMatch};
+
+        const EXAMPLE: &[u8] = b"two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen";
+
+        #[test]
+        fn totals_match_solvers() {
+            let input: &[u8] = include_bytes!("../input/2023/day1.txt");
+
+            assert_eq!(explain_part1(input).total(), solver_part1(input).ok());
+            assert_eq!(explain_part2(input).total(), solver_part2(input).ok());
+            assert_eq!(explain_part2(EXAMPLE).total(), Some(281));
+        }
+
+        #[test]
+        fn overlapping_words() {
+            let explanation = explain_part2(b"xtwone3four\n\neightwo");
+            let found = |value, span: std::ops::Range<usize>, text: &str| DigitMatch { value, span, text: text.to_string() };
+
+            let line = &explanation.lines[0];
+            assert_eq!(line.matches, vec![found(2, 1..4, "two"), found(1, 3..6, "one"), found(3, 6..7, "3"), found(4, 7..11, "four")]);
+            assert_eq!(line.first, Some(found(2, 1..4, "two")));
+            assert_eq!(line.last, Some(found(4, 7..11, "four")));
+            assert_eq!(line.value, Some(24));
+
+            let line = &explanation.lines[1];
+            assert_eq!(line.line, 3);
+            assert_eq!(line.last, Some(found(2, 4..7, "two")));
+            assert_eq!(line.value, Some(82));
+        }
+
+        #[test]
+        fn missing_digits() {
+            let explanation = explain_part1(b"12\nabc");
+
+            assert_eq!(explanation.lines[1].value, None);
+            assert_eq!(explanation.total(), None);
+            assert!(explanation.to_json().ends_with(r#""first":null,"last":null,"value":null}],"total":null}"#));
+        }
+
+        #[test]
+        fn text_report() {
+            let expected = "\
+1  two1nine  => 29
+   2  two   0..3  first
+   1  1     3..4
+   9  nine  4..8  last
+2  7  => 77
+   7  7     0..1  first last
+total: 106
+";
+
+            assert_eq!(explain_part2(b"two1nine\n7").to_text(), expected);
+        }
+
+        #[test]
+        fn json_report() {
+            assert_eq!(
+                explain_part1(b"a1\"").to_json(),
+                r#"{"lines":[{"line":1,"text":"a1\"","matches":[{"value":1,"start":1,"end":2,"text":"1"}],"first":{"value":1,"start":1,"end":2,"text":"1"},"last":{"value":1,"start":1,"end":2,"text":"1"},"value":11}],"total":11}"#
+            );
+        }
+    }
+
+    mod stream {
+        use std::io::BufReader;
+
+        use crate::{
+            day1::{solve_stream_part1, solve_stream_part2, solver_part1, solver_part2, CalibrationError},
+            util::stream::{Repeat, StreamError},
+        };
+
+        const INPUT: &[u8] = include_bytes!("../input/2023/day1.txt");
+
+        #[test]
+        fn agrees_with_slice_solvers() {
+            assert_eq!(solve_stream_part1(INPUT).ok(), solver_part1(INPUT).ok());
+            assert_eq!(solve_stream_part2(INPUT).ok(), solver_part2(INPUT).ok());
+        }
+
+        #[test]
+        fn reports_the_same_line() {
+            assert!(matches!(
+                solve_stream_part1(&b"12\n\nabc"[..]),
+                Err(StreamError::Input(CalibrationError::MissingDigit { line: 3 }))
+            ));
+        }
+
+        #[test]
+        fn repeated_input() {
+            // 100 copies through a small buffer, the lines straddle reads
+            let copies = 100;
+            let reader = BufReader::with_capacity(64, Repeat::new(INPUT, INPUT.len() as u64 * copies));
+
+            assert_eq!(solve_stream_part2(reader).ok(), solver_part2(INPUT).ok().map(|sum| sum * copies as u32));
+        }
+    }
+
     mod prefix_tree {
-        use crate::day1::PrefixTree;
+        use crate::day1::{line_value_part2, PrefixTree};
 
         macro_rules! digit_test {
             ($name:ident, $text:tt, $num:literal) => {
@@ -179,7 +672,7 @@ mod tests {
 
         #[test]
         fn six_detail() {
-            let mut tree = &PrefixTree::create();
+            let mut tree = &PrefixTree::create().forward;
 
             assert!(tree.trees.contains_key(&b's'));
             tree = &tree.trees[&b's'];
@@ -195,6 +688,29 @@ mod tests {
             assert_eq!(tree.value, Some(6));
         }
 
+        #[test]
+        fn find_last_overlaps() {
+            let tree = PrefixTree::create();
+
+            assert_eq!(tree.find_last(b"twone"), Some((1, 2..5)));
+            assert_eq!(tree.find_last(b"eightwo"), Some((2, 4..7)));
+            assert_eq!(tree.find_last(b"eightwoxx"), Some((2, 4..7)));
+            assert_eq!(tree.find_last(b"7twone"), Some((1, 3..6)));
+            assert_eq!(tree.find_last(b"twonx"), Some((2, 0..3)));
+            assert_eq!(tree.find_last(b"xyz"), None);
+            assert_eq!(tree.find_last(b""), None);
+        }
+
+        #[test]
+        fn overlapping_line_values() {
+            let tree = PrefixTree::create();
+
+            assert_eq!(line_value_part2(&tree, b"twone"), Some(21));
+            assert_eq!(line_value_part2(&tree, b"eightwo"), Some(82));
+            assert_eq!(line_value_part2(&tree, b"xtwonex"), Some(21));
+            assert_eq!(line_value_part2(&tree, b"two"), Some(22));
+        }
+
         #[test]
         fn mix_one_eight() {
             let tree = PrefixTree::create();
@@ -237,4 +753,142 @@ mod tests {
             assert!(matches!(tree.find(b"eightthree"), Some((8, _))));
         }
     }
+
+    mod swar {
+        use proptest::prelude::*;
+
+        use crate::day1::{
+            byte_mask, digit_mask, line_value_part1, line_value_part1_swar, solver_part1, solver_part1_swar, LinesSwar,
+        };
+
+        #[test]
+        fn mask_is_exact_per_byte() {
+            for byte in 0..=u8::MAX {
+                let word = u64::from_le_bytes([b'x', byte, b'9', 0xff, byte, b'0', b'/', b':']);
+                let expected = [false, byte.is_ascii_digit(), true, false, byte.is_ascii_digit(), true, false, false]
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &digit)| digit)
+                    .fold(0, |mask, (idx, _)| mask | 0x80 << (idx * 8));
+
+                assert_eq!(digit_mask(word), expected, "byte {byte}");
+            }
+        }
+
+        #[test]
+        fn byte_mask_is_exact_per_byte() {
+            for byte in 0..=u8::MAX {
+                let word = u64::from_le_bytes([b'\n', byte, 0x8a, 0x0b, byte, 0x09, 0, b'\n']);
+                let expected = [true, byte == b'\n', false, false, byte == b'\n', false, false, true]
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &newline)| newline)
+                    .fold(0, |mask, (idx, _)| mask | 0x80 << (idx * 8));
+
+                assert_eq!(byte_mask(word, b'\n'), expected, "byte {byte}");
+            }
+        }
+
+        #[test]
+        fn real_input() {
+            let input: &[u8] = include_bytes!("../input/2023/day1.txt");
+
+            assert_eq!(solver_part1_swar(input), solver_part1(input));
+        }
+
+        fn line() -> impl Strategy<Value = Vec<u8>> {
+            prop::collection::vec(
+                prop_oneof![
+                    4 => prop::sample::select(b"abcxyz/:".to_vec()),
+                    1 => prop::sample::select(b"0123456789".to_vec()),
+                    1 => any::<u8>().prop_filter("no newlines", |&c| c != b'\n'),
+                ],
+                0..40,
+            )
+        }
+
+        proptest! {
+            #[test]
+            fn line_values_agree(line in line()) {
+                prop_assert_eq!(line_value_part1_swar(&line), line_value_part1(&line));
+            }
+
+            #[test]
+            fn lines_agree(input in prop::collection::vec(prop_oneof![4 => any::<u8>(), 1 => Just(b'\n')], 0..80)) {
+                let lines = LinesSwar::new(&input).collect::<Vec<_>>();
+
+                prop_assert_eq!(lines, input.split(|&x| x == b'\n').collect::<Vec<_>>());
+            }
+
+            #[test]
+            fn sums_agree(lines in prop::collection::vec(line(), 0..20)) {
+                let input = lines.join(&b'\n');
+
+                prop_assert_eq!(solver_part1_swar(&input), solver_part1(&input));
+            }
+        }
+    }
+
+    mod prefix_tree_properties {
+        use proptest::prelude::*;
+
+        use crate::day1::PrefixTree;
+
+        const WORDS: [&str; 18] = [
+            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+            "1", "2", "3", "4", "5", "6", "7", "8", "9",
+        ];
+
+        // shortest dictionary word the sequence starts with
+        fn naive_find(sequence: &[u8]) -> Option<(u32, usize)> {
+            WORDS.iter()
+                .enumerate()
+                .filter(|(_, word)| sequence.starts_with(word.as_bytes()))
+                .map(|(idx, word)| (idx as u32 % 9 + 1, word.len()))
+                .min_by_key(|&(_, length)| length)
+        }
+
+        // the word ending furthest right, as (value, span)
+        fn naive_find_last(sequence: &[u8]) -> Option<(u32, std::ops::Range<usize>)> {
+            (0..sequence.len())
+                .filter_map(|start| naive_find(&sequence[start..]).map(|(value, length)| (value, start..start + length)))
+                .max_by_key(|(_, span)| span.end)
+        }
+
+        // mostly letters from the dictionary so that matches are frequent
+        fn sequence() -> impl Strategy<Value = Vec<u8>> {
+            prop::collection::vec(
+                prop_oneof![
+                    4 => prop::sample::select(b"onetwhrfuivsxg".to_vec()),
+                    1 => prop::sample::select(b"0123456789".to_vec()),
+                    1 => any::<u8>(),
+                ],
+                0..16,
+            )
+        }
+
+        proptest! {
+            #[test]
+            fn find_matches_naive_scan(sequence in sequence()) {
+                prop_assert_eq!(PrefixTree::create().find(&sequence), naive_find(&sequence));
+            }
+
+            #[test]
+            fn find_matches_naive_scan_with_word_prefix(word in prop::sample::select(WORDS.to_vec()), rest in sequence()) {
+                let sequence = [word.as_bytes(), &rest].concat();
+
+                prop_assert_eq!(PrefixTree::create().find(&sequence), naive_find(&sequence));
+            }
+
+            #[test]
+            fn find_on_arbitrary_bytes(sequence in prop::collection::vec(any::<u8>(), 0..32)) {
+                prop_assert_eq!(PrefixTree::create().find(&sequence), naive_find(&sequence));
+            }
+
+            #[test]
+            fn find_last_matches_naive_scan(sequence in sequence()) {
+                prop_assert_eq!(PrefixTree::create().find_last(&sequence), naive_find_last(&sequence));
+            }
+        }
+    }
 }
@@ -1,7 +1,16 @@
+use std::{error::Error, fmt::Display, io::BufRead};
+
 use aoc_runner_derive::{aoc_generator, aoc};
 
+use crate::util::{
+    parse::{alt, delimited, map, map_res, opt, pair, parse_all, sep_by1, tag, terminated, uint, ParseError, Parser},
+    stream::{for_each_line, StreamError},
+    CheckedAccumulate, OverflowError,
+};
+
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Hand {
     red: u32,
     green: u32,
@@ -22,6 +31,8 @@ impl Hand {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     attempts: Vec<Hand>,
 }
@@ -34,64 +45,176 @@ impl Game {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Colour {
+    Red,
+    Green,
+    Blue,
+}
+
+// Game <id>: <count> <colour>, ...; <count> <colour>, ...
+fn game<'a>() -> impl Parser<'a, (u32, Game)> {
+    let colour = alt(
+        alt(
+            map(tag(b"red"), |_| Colour::Red),
+            map(tag(b"green"), |_| Colour::Green),
+        ),
+        map(tag(b"blue"), |_| Colour::Blue),
+    );
+    let draw = pair(terminated(uint::<u32>(), tag(b" ")), colour);
+
+    // a colour may only be drawn once per hand
+    let hand = map_res(sep_by1(draw, tag(b", ")), "each colour at most once per hand", |draws: Vec<(u32, Colour)>| {
+        draws.iter().try_fold(Hand::new(), |mut hand, &(count, colour)| {
+            if draws.iter().filter(|(_, other)| *other == colour).count() > 1 {
+                return None;
+            }
+
+            match colour {
+                Colour::Red => hand.red = count,
+                Colour::Green => hand.green = count,
+                Colour::Blue => hand.blue = count,
+            }
+            Some(hand)
+        })
+    });
+
+    pair(
+        delimited(tag(b"Game "), uint::<u32>(), tag(b": ")),
+        map(sep_by1(hand, tag(b"; ")), Game::with_hands),
+    )
+}
+
 #[aoc_generator(day2)]
-pub fn generator(input: &[u8]) -> Vec<(u32, Game)> {
-    fn parse_hand(input: &[u8]) -> Hand {
-        let mut hand = Hand::new();
-        let mut idx = 1;
-        while idx < input.len() {
-            // index of space since idx
-            let space_idx = input[idx..].iter().position(|&c| c == b' ').unwrap();
-
-            // number between idx and idx of space
-            let number = std::str::from_utf8(&input[idx..idx + space_idx]).unwrap().parse().unwrap();
-
-            // space and include starting character
-            idx += space_idx + 1;
-            match input[idx] {
-                b'r' => {
-                    hand.red = number;
-                    idx += "red".len();
-                },
-                b'g' => {
-                    hand.green = number;
-                    idx += "green".len();
-                },
-                b'b' => {
-                    hand.blue= number;
-                    idx += "blue".len();
-                },
-                _ => panic!("Unaccepted character '{}' in position {} of substring {}", input[idx] as char, idx, std::str::from_utf8(input).unwrap()),
-            };
-            // comma space
-            idx += 2;
-        }
+pub fn generator(input: &[u8]) -> Result<Vec<(u32, Game)>, ParseError> {
+    parse_all(terminated(sep_by1(game(), tag(b"\n")), opt(tag(b"\n"))), input)
+}
 
-        hand
-    }
+fn is_possible(game: &Game) -> bool {
+    game.attempts.iter().all(|hand| hand.red <= 12 && hand.green <= 13 && hand.blue <= 14)
+}
+
+// product of the fewest cubes of each colour the game could be played with
+fn power(game: &Game) -> Result<u32, OverflowError> {
+    let hand = game.attempts.iter().copied().fold(Hand::new(), Hand::max);
 
-    input.split(|&char| char == b'\n')
-        .map(|slice| &slice[slice.iter().position(|&char| char == b':').unwrap() + 1..])
-        .map(|slice| slice.split(|&char| char == b';').map(parse_hand).collect::<Vec<_>>())
-        .map(Game::with_hands)
-        .enumerate()
-        .map(|(idx, game)| (idx as u32 + 1, game))
-        .collect::<Vec<_>>()
+    [hand.red, hand.green, hand.blue].into_iter().checked_product()
 }
 
 #[aoc(day2, part1)]
-pub fn part1_solver(input: &[(u32, Game)]) -> u32 {
+pub fn part1_solver(input: &[(u32, Game)]) -> Result<u32, OverflowError> {
     input.iter()
-        .filter(|(_, game)| game.attempts.iter().all(|hand| hand.red <= 12 && hand.green <= 13 && hand.blue <= 14))
-        .map(|(id, _)| id)
-        .sum()
+        .filter(|(_, game)| is_possible(game))
+        .map(|&(id, _)| id)
+        .checked_sum()
 }
 
 
 #[aoc(day2, part2)]
-pub fn part2_solver(input: &[(u32, Game)]) -> u32 {
+pub fn part2_solver(input: &[(u32, Game)]) -> Result<u32, OverflowError> {
     input.iter()
-        .map(|(_, game)| game.attempts.iter().copied().fold(Hand::new(), Hand::max))
-        .map(|hand| hand.red * hand.green * hand.blue)
-        .sum()
+        .map(|(_, game)| power(game))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .checked_sum()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    Parse(ParseError),
+    Overflow,
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(error) => write!(f, "{error}"),
+            Self::Overflow => write!(f, "{}", OverflowError),
+        }
+    }
+}
+
+impl Error for GameError {}
+
+impl From<OverflowError> for GameError {
+    fn from(_: OverflowError) -> Self {
+        Self::Overflow
+    }
+}
+
+// sum of `value` over the games of `reader`, parsed one line at a time;
+// parse errors carry their position in the whole stream like `generator`'s
+fn sum_stream<R: BufRead>(reader: R, value: impl Fn(u32, &Game) -> Result<u32, OverflowError>) -> Result<u32, StreamError<GameError>> {
+    let mut total = 0u32;
+
+    for_each_line(reader, |offset, line| {
+        let (id, game) = parse_all(game(), line)
+            .map_err(|error| GameError::Parse(ParseError::new(offset + error.position, error.expected)))?;
+
+        total = total.checked_add(value(id, &game)?).ok_or(OverflowError)?;
+        Ok(())
+    })?;
+
+    Ok(total)
+}
+
+pub fn solve_stream_part1<R: BufRead>(reader: R) -> Result<u32, StreamError<GameError>> {
+    sum_stream(reader, |id, game| Ok(if is_possible(game) { id } else { 0 }))
+}
+
+pub fn solve_stream_part2<R: BufRead>(reader: R) -> Result<u32, StreamError<GameError>> {
+    sum_stream(reader, |_, game| power(game))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &[u8] = b"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+
+    #[test]
+    fn example() {
+        let games = generator(EXAMPLE).unwrap();
+
+        assert_eq!(part1_solver(&games), Ok(8));
+        assert_eq!(part2_solver(&games), Ok(2286));
+    }
+
+    #[test]
+    fn rejects_malformed_games() {
+        let error = |input: &[u8]| generator(input).err().map(|e| e.position);
+
+        assert_eq!(error(b"Game 1: 3 blue, 4 purple"), Some(18));
+        assert_eq!(error(b"Game 1: 3 blue,4 red"), Some(14));
+        assert_eq!(error(b"Game 1: 3 blue, 2 blue"), Some(8));
+        assert_eq!(error(b"Game x: 3 blue"), Some(5));
+        assert_eq!(error(b"Game 1: 3 blue;"), Some(14));
+    }
+
+    #[test]
+    fn stream_agrees_with_generator() {
+        let input: &[u8] = include_bytes!("../input/2023/day2.txt");
+        let games = generator(input).unwrap();
+
+        assert_eq!(solve_stream_part1(EXAMPLE).unwrap(), 8);
+        assert_eq!(solve_stream_part2(EXAMPLE).unwrap(), 2286);
+        assert_eq!(solve_stream_part1(input).ok(), part1_solver(&games).ok());
+        assert_eq!(solve_stream_part2(input).ok(), part2_solver(&games).ok());
+    }
+
+    #[test]
+    fn stream_errors_point_into_the_whole_input() {
+        let input = b"Game 1: 3 blue\nGame 2: 3 blue, 4 purple\n";
+        let position = |result| match result {
+            Err(StreamError::Input(GameError::Parse(error))) => Some(error.position),
+            _ => None,
+        };
+
+        assert_eq!(position(solve_stream_part1(&input[..])), generator(input).err().map(|e| e.position));
+    }
 }
@@ -2,14 +2,62 @@ use std::fmt::Debug;
 
 use aoc_runner_derive::{aoc_generator, aoc};
 
+use crate::util::{
+    interval::Interval,
+    parse::{alt, many1, map, map_res, opt, parse_all, satisfy, sep_by1, tag, take_while1, terminated, ParseError},
+    render::Svg,
+    CheckedAccumulate, OverflowError,
+};
+
 type CoordType = u16;
 type ValueType = u16;
+// answers are accumulated in a wider type than the values they are built from
+type SumType = u32;
 
 type Point = crate::util::Point<CoordType>;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Symbol {
+    Gear,
+    Hash,
+    Plus,
+    Dollar,
+    Other(u8),
+}
+
+impl Symbol {
+    pub const fn from_byte(byte: u8) -> Self {
+        match byte {
+            b'*' => Self::Gear,
+            b'#' => Self::Hash,
+            b'+' => Self::Plus,
+            b'$' => Self::Dollar,
+            other => Self::Other(other),
+        }
+    }
+
+    pub const fn as_byte(self) -> u8 {
+        match self {
+            Self::Gear => b'*',
+            Self::Hash => b'#',
+            Self::Plus => b'+',
+            Self::Dollar => b'$',
+            Self::Other(byte) => byte,
+        }
+    }
+}
+
+impl From<u8> for Symbol {
+    fn from(byte: u8) -> Self {
+        Self::from_byte(byte)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Entry {
-    Symbol(u8, Point),
+    Symbol(Symbol, Point),
     Number(ValueType, Point, Point),
 }
 
@@ -17,7 +65,7 @@ impl Entry {
     pub const fn is_symbol(&self) -> bool { matches!(self, Self::Symbol(..)) }
     pub const fn is_number(&self) -> bool { matches!(self, Self::Number(..)) }
 
-    pub const fn unwrap_symbol(self) -> (u8, Point) {
+    pub const fn unwrap_symbol(self) -> (Symbol, Point) {
         match self {
             Self::Symbol(v, p) => (v, p),
             _ => panic!("Cannot unwrap Symbol from non-Symbol value.")
@@ -27,72 +75,90 @@ impl Entry {
     pub const fn unwrap_number(self) -> (ValueType, (Point, Point)) {
         match self {
             Self::Number(v, p1, p2) => (v, (p1, p2)),
-            _ => panic!("Cannot unwrap Symbol from non-Symbol value.")
+            _ => panic!("Cannot unwrap Number from non-Number value.")
         }
     }
 
-    pub fn bounding_box(&self, max_height: CoordType, max_width: CoordType) -> (Point, Point) {
-        let left = match self {
-            Entry::Symbol(_, p) | Entry::Number(_, p, _) => p,
-        };
-
-        let right = match self {
-            Entry::Symbol(_, p) | Entry::Number(_, _, p) => p,
-        };
-
-        let upper_left = Point {
-            x: left.x.checked_sub(1).unwrap_or(0),
-            y: left.y.checked_sub(1).unwrap_or(0),
-        };
+    pub const fn is_symbol_kind(&self, kind: Symbol) -> bool {
+        match self {
+            Self::Symbol(symbol, _) => symbol.as_byte() == kind.as_byte(),
+            _ => false,
+        }
+    }
 
-        let lower_right = Point {
-            x: max_width.min(right.x + 1),
-            y: max_height.min(right.y + 1),
-        };
+    // the cells occupied by the entry itself
+    pub const fn area(&self) -> Area {
+        match *self {
+            Self::Symbol(_, p) => (p, p),
+            Self::Number(_, left, right) => (left, right),
+        }
+    }
 
-        (upper_left, lower_right)
+    pub fn bounding_box(&self, max_height: CoordType, max_width: CoordType) -> (Point, Point) {
+        expand_area::<1>(self.area(), max_height, max_width)
     }
 }
 
-#[aoc_generator(day3)]
-pub fn generator(input: &[u8]) -> (CoordType, CoordType, Vec<Entry>) {
-    let width = input.iter().position(|&c| c == b'\n').unwrap();
-    let height = input.iter().filter(|&&c| c == b'\n').count() + 1;
-
-    let entries = input.split(|&char| char == b'\n')
-        .enumerate()
-        .fold(Vec::new(), |mut entries, (line_idx, line)| {
-            let mut idx = 0;
-
-            while idx < line.len() {
-                // parse a sequence of digits into Entry::Number
-                if line[idx].is_ascii_digit() {
-                    let number_length = line[idx..].iter().copied().take_while(u8::is_ascii_digit).count();
-                    let number = std::str::from_utf8(&line[idx..idx + number_length]).unwrap().parse().unwrap();
-
-                    let left = Point::from((idx, line_idx));
+enum Token {
+    // value and number of digits
+    Number(ValueType, usize),
+    Dot,
+    Symbol(u8),
+}
 
-                    idx += number_length;
+// rows of equal width made of numbers, dots and any other printable symbol
+#[aoc_generator(day3)]
+pub fn generator(input: &[u8]) -> Result<(CoordType, CoordType, Vec<Entry>), ParseError> {
+    let number = map_res(
+        take_while1("digit", |c| c.is_ascii_digit()),
+        "number in range",
+        |digits: &[u8]| Some(Token::Number(std::str::from_utf8(digits).ok()?.parse().ok()?, digits.len())),
+    );
+    let token = alt(
+        alt(number, map(tag(b"."), |_| Token::Dot)),
+        map(satisfy("symbol", |c| c.is_ascii_graphic() && !c.is_ascii_digit() && c != b'.'), Token::Symbol),
+    );
+
+    let lines = parse_all(terminated(sep_by1(many1(token), tag(b"\n")), opt(tag(b"\n"))), input)?;
+
+    let width = lines[0].iter().map(|token| match token {
+        Token::Number(_, length) => *length,
+        _ => 1,
+    }).sum::<usize>();
+
+    let mut entries = Vec::new();
+
+    for (line_idx, line) in lines.into_iter().enumerate() {
+        let line_start = line_idx * (width + 1);
+        let point = |idx: usize| {
+            Point::try_from((idx, line_idx)).map_err(|_| ParseError::new(line_start + idx, "coordinates within the coordinate type"))
+        };
 
-                    let right = Point::from((idx - 1, line_idx));
+        let mut idx = 0;
 
-                    entries.push(Entry::Number(number, left, right));
-                }
-                // parse non-dot symbol into Entry::Symbol
-                else if line[idx] != b'.' {
-                    entries.push(Entry::Symbol(line[idx], Point::from((idx, line_idx))));
-                    idx += 1;
-                }
-                // dot; skip
-                else {
+        for token in line {
+            match token {
+                Token::Number(value, length) => {
+                    entries.push(Entry::Number(value, point(idx)?, point(idx + length - 1)?));
+                    idx += length;
+                },
+                Token::Symbol(symbol) => {
+                    entries.push(Entry::Symbol(symbol.into(), point(idx)?));
                     idx += 1;
-                }
+                },
+                Token::Dot => idx += 1,
             }
+        }
+
+        if idx != width {
+            return Err(ParseError::new(line_start + idx.min(width), format!("line of width {width}")));
+        }
+    }
 
-            entries
-        });
+    let height = input.split(|&c| c == b'\n').filter(|line| !line.is_empty()).count();
+    let to_coord = |value: usize| num_traits::cast(value).ok_or_else(|| ParseError::new(input.len(), "schematic within the coordinate type"));
 
-    (num_traits::cast(width).unwrap(), num_traits::cast(height).unwrap(), entries)
+    Ok((to_coord(width)?, to_coord(height)?, entries))
 }
 
 // I think it is?
@@ -118,22 +184,26 @@ fn calc_bounding_area<T>(areas: &[(T, Area)]) -> Option<Area> {
     let x1 = areas.iter().map(|(_, (_, right))| right.x).max()?;
     let y1 = areas.iter().map(|(_, (_, right))| right.y).max()?;
 
-    Some((Point::from((x0, y0)), Point::from((x1, y1))))
+    Some((Point::new(x0, y0), Point::new(x1, y1)))
 }
 
-const fn contains_point(area: &Area, Point {x: x0, y: y0}: Point) -> bool {
-    let &(Point {x: x1, y: y1}, Point {x: x2, y: y2}) = area;
-
-    x1 <= x0 && y1 <= y0 &&
-    x0 <= x2 && y0 <= y2
+// an area is the product of its column and row intervals, `None` if the
+// corners are out of order
+fn area_axes(&(tl, br): &Area) -> Option<(Interval<CoordType>, Interval<CoordType>)> {
+    Some((Interval::new(tl.x, br.x)?, Interval::new(tl.y, br.y)?))
 }
 
+pub fn contains_point(area: &Area, point: Point) -> bool {
+    area_axes(area).is_some_and(|(columns, rows)| columns.contains(point.x) && rows.contains(point.y))
+}
 
-pub const fn intersects_area(area: &Area, (Point {x: x0, y: y0}, Point {x: x1, y: y1}): &Area) -> bool {
-    contains_point(area, Point::new(*x0, *y0)) || 
-    contains_point(area, Point::new(*x1, *y0)) || 
-    contains_point(area, Point::new(*x0, *y1)) || 
-    contains_point(area, Point::new(*x1, *y1))
+pub fn intersects_area(a: &Area, b: &Area) -> bool {
+    match (area_axes(a), area_axes(b)) {
+        (Some((a_columns, a_rows)), Some((b_columns, b_rows))) => {
+            a_columns.intersects(&b_columns) && a_rows.intersects(&b_rows)
+        },
+        _ => false,
+    }
 }
 
 type Area = (Point, Point);
@@ -142,7 +212,7 @@ impl<'b, T: Debug + Clone> BoundingAreaHierarchy<T> {
     pub const fn is_leaf(&self) -> bool { matches!(self, Self::Leaf{ .. }) }
     pub const fn is_empty(&self) -> bool { matches!(self, Self::Empty) }
 
-    pub const fn contains(&self, point: Point) -> bool {
+    pub fn contains(&self, point: Point) -> bool {
         let area = match self {
             Self::Node { bounding_area: area, .. } | Self::Leaf { area, .. } => area,
             _ => return false
@@ -151,16 +221,13 @@ impl<'b, T: Debug + Clone> BoundingAreaHierarchy<T> {
         contains_point(area, point)
     }
 
-    pub const fn intersects(&self, (Point {x: x0, y: y0}, Point {x: x1, y: y1}): Area) -> bool {
+    pub fn intersects(&self, target: Area) -> bool {
         let area = match self {
             Self::Node { bounding_area: area, .. } | Self::Leaf { area, .. } => area,
             _ => return false
         };
 
-        contains_point(area, Point::new(x0, y0)) || 
-        contains_point(area, Point::new(x1, y0)) || 
-        contains_point(area, Point::new(x0, y1)) || 
-        contains_point(area, Point::new(x1, y1))
+        intersects_area(area, &target)
     }
 
     pub fn from_areas(mut areas: Vec<(T, Area)>) -> Self {
@@ -181,7 +248,7 @@ impl<'b, T: Debug + Clone> BoundingAreaHierarchy<T> {
             let (value, area) = areas.first().unwrap();
             Self::Leaf { area: *area, value: value.clone() }
         } else {
-            let bounding_area = calc_bounding_area(&areas).unwrap();
+            let bounding_area = calc_bounding_area(areas).unwrap();
 
             // println!("L: {}  D: {}", areas.len(), if dim & 1 == 0 {'x'} else {'y'});
             // println!("{areas:?}");
@@ -192,6 +259,11 @@ impl<'b, T: Debug + Clone> BoundingAreaHierarchy<T> {
 
             let lower_count = areas.iter_mut().partition_in_place(|a| area_dim_key(a, dim) <= partition_value);
 
+            // duplicate keys can put every area on one side, which would
+            // recurse on the same slice forever; the slice is still sorted
+            // then, so halving it is a valid split
+            let lower_count = if lower_count == areas.len() { areas.len() / 2 } else { lower_count };
+
             Self::Node {
                 bounding_area,
                 left: Box::new(Self::build_bah(&mut areas[..lower_count], dim + 1)),
@@ -206,7 +278,7 @@ impl<'b, T: Debug + Clone> BoundingAreaHierarchy<T> {
                 left.contains_intersection(target) ||
                 right.contains_intersection(target)
             },
-            Self::Leaf { area, .. } if intersects_area(area, &target) => {
+            Self::Leaf { area, .. } if intersects_area(area, target) => {
                 true
             }
             _ => false,
@@ -214,80 +286,703 @@ impl<'b, T: Debug + Clone> BoundingAreaHierarchy<T> {
     }
 
     pub fn search_intersections(&self, target: &Area) -> Vec<&T> {
+        self.intersecting_leaves(target).into_iter().map(|(_, value)| value).collect()
+    }
+
+    // like `search_intersections` but with the area each leaf was stored under
+    pub fn intersecting_leaves(&self, target: &Area) -> Vec<(&Area, &T)> {
         let mut buffer = Vec::with_capacity(1);
         self.recursive_intersection_search(target, &mut buffer);
-        return buffer;
+        buffer
+    }
+
+    pub fn leaves(&self) -> Vec<(&Area, &T)> {
+        let mut buffer = Vec::new();
+        self.recursive_leaf_collect(&mut buffer);
+        buffer
+    }
+
+    fn recursive_leaf_collect(&'b self, leaves: &mut Vec<(&'b Area, &'b T)>) {
+        match self {
+            Self::Node { left, right, .. } => {
+                left.recursive_leaf_collect(leaves);
+                right.recursive_leaf_collect(leaves);
+            },
+            Self::Leaf { area, value } => leaves.push((area, value)),
+            Self::Empty => (),
+        }
     }
 
-    fn recursive_intersection_search(&'b self, target: &Area, intersections: &mut Vec<&'b T>) {
+    fn recursive_intersection_search(&'b self, target: &Area, intersections: &mut Vec<(&'b Area, &'b T)>) {
         match self {
             Self::Node { bounding_area, left, right } if intersects_area(bounding_area, target) => {
                 left.recursive_intersection_search(target, intersections);
                 right.recursive_intersection_search(target, intersections);
             },
-            Self::Leaf { area, value } if intersects_area(&area, target) =>  {
-                println!("{area:?} ({value:?}) contains {target:?}");
-                intersections.push(value)
+            Self::Leaf { area, value } if intersects_area(area, target) =>  {
+                intersections.push((area, value))
             }
             _ => (),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HierarchyStats {
+    pub depth: usize,
+    pub node_count: usize,
+    pub leaf_count: usize,
+    pub empty_count: usize,
+    pub min_leaf_depth: usize,
+    pub max_leaf_depth: usize,
+    pub mean_leaf_depth: f64,
+    // largest difference in leaf count between the two children of a node
+    pub max_imbalance: usize,
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl<T: Debug + Clone> BoundingAreaHierarchy<T> {
+    pub fn stats(&self) -> HierarchyStats {
+        // returns the leaf count of the subtree
+        fn visit<T>(tree: &BoundingAreaHierarchy<T>, depth: usize, stats: &mut HierarchyStats, leaf_depths: &mut usize) -> usize {
+            stats.depth = stats.depth.max(depth);
+
+            match tree {
+                BoundingAreaHierarchy::Node { left, right, .. } => {
+                    stats.node_count += 1;
+                    let left = visit(left, depth + 1, stats, leaf_depths);
+                    let right = visit(right, depth + 1, stats, leaf_depths);
+                    stats.max_imbalance = stats.max_imbalance.max(left.abs_diff(right));
+                    left + right
+                },
+                BoundingAreaHierarchy::Leaf { .. } => {
+                    stats.leaf_count += 1;
+                    stats.min_leaf_depth = stats.min_leaf_depth.min(depth);
+                    stats.max_leaf_depth = stats.max_leaf_depth.max(depth);
+                    *leaf_depths += depth;
+                    1
+                },
+                BoundingAreaHierarchy::Empty => {
+                    stats.empty_count += 1;
+                    0
+                },
+            }
+        }
+
+        let mut stats = HierarchyStats {
+            depth: 0,
+            node_count: 0,
+            leaf_count: 0,
+            empty_count: 0,
+            min_leaf_depth: usize::MAX,
+            max_leaf_depth: 0,
+            mean_leaf_depth: 0.0,
+            max_imbalance: 0,
+        };
+        let mut leaf_depths = 0;
+
+        visit(self, 0, &mut stats, &mut leaf_depths);
+
+        if stats.leaf_count == 0 {
+            stats.min_leaf_depth = 0;
+        } else {
+            stats.mean_leaf_depth = leaf_depths as f64 / stats.leaf_count as f64;
+        }
+
+        stats
+    }
+
+    pub fn to_dot(&self) -> String {
+        fn visit<T: Debug>(tree: &BoundingAreaHierarchy<T>, depth: usize, next_id: &mut usize, dot: &mut String) -> usize {
+            let id = *next_id;
+            *next_id += 1;
+
+            match tree {
+                BoundingAreaHierarchy::Node { bounding_area: (tl, br), left, right } => {
+                    dot.push_str(&format!("    n{id} [label=\"depth {depth}\\n{tl:?}\\n{br:?}\", shape=box];\n"));
+                    for child in [left, right] {
+                        let child = visit(child, depth + 1, next_id, dot);
+                        dot.push_str(&format!("    n{id} -> n{child};\n"));
+                    }
+                },
+                BoundingAreaHierarchy::Leaf { area: (tl, br), value } => {
+                    let value = dot_escape(&format!("{value:?}"));
+                    dot.push_str(&format!("    n{id} [label=\"{value}\\n{tl:?}\\n{br:?}\", shape=ellipse];\n"));
+                },
+                BoundingAreaHierarchy::Empty => {
+                    dot.push_str(&format!("    n{id} [label=\"empty\", shape=point];\n"));
+                },
+            }
+
+            id
+        }
+
+        let mut dot = String::from("digraph hierarchy {\n");
+        visit(self, 0, &mut 0, &mut dot);
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_json(&self) -> String {
+        fn area_json((tl, br): &Area) -> String {
+            format!("[{{\"x\":{},\"y\":{}}},{{\"x\":{},\"y\":{}}}]", tl.x, tl.y, br.x, br.y)
+        }
+
+        fn visit<T: Debug>(tree: &BoundingAreaHierarchy<T>, depth: usize, json: &mut String) {
+            match tree {
+                BoundingAreaHierarchy::Node { bounding_area, left, right } => {
+                    json.push_str(&format!("{{\"type\":\"node\",\"depth\":{depth},\"area\":{},\"left\":", area_json(bounding_area)));
+                    visit(left, depth + 1, json);
+                    json.push_str(",\"right\":");
+                    visit(right, depth + 1, json);
+                    json.push('}');
+                },
+                BoundingAreaHierarchy::Leaf { area, value } => {
+                    json.push_str(&format!(
+                        "{{\"type\":\"leaf\",\"depth\":{depth},\"area\":{},\"value\":\"{}\"}}",
+                        area_json(area),
+                        crate::util::json_escape(&format!("{value:?}"))
+                    ));
+                },
+                BoundingAreaHierarchy::Empty => {
+                    json.push_str(&format!("{{\"type\":\"empty\",\"depth\":{depth}}}"));
+                },
+            }
+        }
+
+        let mut json = String::new();
+        visit(self, 0, &mut json);
+        json
+    }
+}
+
+impl BoundingAreaHierarchy<Entry> {
+    pub fn from_entries(entries: &[Entry]) -> Self {
+        entries.iter().map(|&entry| (entry, entry.area())).collect()
+    }
+
+    // every other entry that touches the neighbourhood of `entry`
+    pub fn adjacent(&self, entry: &Entry, max_height: CoordType, max_width: CoordType) -> Vec<&Entry> {
+        self.search_intersections(&entry.bounding_box(max_height, max_width))
+            .into_iter()
+            .filter(|&other| other != entry)
+            .collect()
+    }
+
+    pub fn numbers_adjacent_to(&self, entry: &Entry, max_height: CoordType, max_width: CoordType) -> Vec<(ValueType, Area)> {
+        self.adjacent(entry, max_height, max_width)
+            .into_iter()
+            .filter(|other| other.is_number())
+            .map(|&other| other.unwrap_number())
+            .collect()
+    }
+
+    pub fn symbols_adjacent_to(&self, entry: &Entry, max_height: CoordType, max_width: CoordType) -> Vec<(Symbol, Point)> {
+        self.adjacent(entry, max_height, max_width)
+            .into_iter()
+            .filter(|other| other.is_symbol())
+            .map(|&other| other.unwrap_symbol())
+            .collect()
+    }
+
+    // numbers touching at least one symbol of the given kind, each reported once
+    pub fn numbers_adjacent_to_kind(&self, kind: Symbol, max_height: CoordType, max_width: CoordType) -> Vec<(ValueType, Area)> {
+        let mut numbers = self.leaves()
+            .into_iter()
+            .filter(|(_, entry)| entry.is_symbol_kind(kind))
+            .flat_map(|(_, entry)| self.numbers_adjacent_to(entry, max_height, max_width))
+            .collect::<Vec<_>>();
+
+        numbers.sort_by_key(|(_, (left, _))| (left.y, left.x));
+        numbers.dedup();
+        numbers
+    }
+}
+
 impl<V: Debug + Clone> FromIterator<(V, Area)> for BoundingAreaHierarchy<V> {
     fn from_iter<T: IntoIterator<Item = (V, Area)>>(iter: T) -> Self {
         Self::from_areas(iter.into_iter().collect())
     }
 }
 
-fn expand_area<const E: CoordType>((tl, br): Area, max_height: CoordType, max_width: CoordType) -> Area {
-    let left = tl.x.checked_sub(E).unwrap_or(0);
-    let top = tl.y.checked_sub(E).unwrap_or(0);
-    let right = max_width.min(br.x + E);
-    let bot = max_height.min(br.y + E);
+// bipartite graph between numbers and the symbols they touch, nodes are
+// indices into `nodes` and edges only ever join a number to a symbol
+#[derive(Debug, Clone)]
+pub struct AdjacencyGraph {
+    nodes: Vec<Entry>,
+    edges: Vec<Vec<usize>>,
+}
+
+impl AdjacencyGraph {
+    pub fn build(width: CoordType, height: CoordType, entries: &[Entry]) -> Self {
+        let nodes = entries.to_vec();
+        let mut edges = vec![Vec::new(); nodes.len()];
+
+        let symbols = nodes.iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.is_symbol())
+            .map(|(idx, entry)| (idx, entry.area()))
+            .collect::<BoundingAreaHierarchy<_>>();
+
+        for (number, entry) in nodes.iter().enumerate().filter(|(_, entry)| entry.is_number()) {
+            for &symbol in symbols.search_intersections(&entry.bounding_box(height, width)) {
+                edges[number].push(symbol);
+                edges[symbol].push(number);
+            }
+        }
+
+        edges.iter_mut().for_each(|neighbours| neighbours.sort_unstable());
+
+        Self { nodes, edges }
+    }
+
+    pub fn nodes(&self) -> &[Entry] {
+        &self.nodes
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    pub fn neighbours(&self, node: usize) -> &[usize] {
+        &self.edges[node]
+    }
+
+    pub fn degree(&self, node: usize) -> usize {
+        self.edges[node].len()
+    }
+
+    // (number, symbol) pairs
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.edges.iter()
+            .enumerate()
+            .filter(|&(idx, _)| self.nodes[idx].is_number())
+            .flat_map(|(idx, neighbours)| neighbours.iter().map(move |&symbol| (idx, symbol)))
+    }
+
+    pub fn nodes_with_degree(&self, degree: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(move |&idx| self.degree(idx) == degree)
+    }
+
+    // connected components as sorted node lists, ordered by their smallest node
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut components = Vec::new();
+
+        for start in 0..self.nodes.len() {
+            if visited[start] {
+                continue;
+            }
+
+            visited[start] = true;
+            let mut component = vec![start];
+            let mut stack = vec![start];
+
+            while let Some(node) = stack.pop() {
+                for &next in &self.edges[node] {
+                    if !visited[next] {
+                        visited[next] = true;
+                        component.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+
+            component.sort_unstable();
+            components.push(component);
+        }
+
+        components
+    }
+
+    pub fn part_number_sum(&self) -> Result<SumType, OverflowError> {
+        self.nodes.iter()
+            .enumerate()
+            .filter(|&(idx, entry)| entry.is_number() && self.degree(idx) > 0)
+            .map(|(_, entry)| entry.unwrap_number().0)
+            .checked_sum()
+    }
+
+    pub fn gear_ratio_sum(&self) -> Result<SumType, OverflowError> {
+        self.nodes.iter()
+            .enumerate()
+            .filter(|&(idx, entry)| entry.is_symbol_kind(Symbol::Gear) && self.degree(idx) == 2)
+            .map(|(idx, _)| {
+                self.edges[idx].iter()
+                    .map(|&number| self.nodes[number].unwrap_number().0)
+                    .checked_product::<SumType>()
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .checked_sum()
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph adjacency {\n");
+
+        for (idx, entry) in self.nodes.iter().enumerate() {
+            let (label, shape, position) = match *entry {
+                Entry::Number(value, left, _) => (value.to_string(), "box", left),
+                Entry::Symbol(symbol, point) => ((symbol.as_byte() as char).to_string(), "circle", point),
+            };
+
+            dot.push_str(&format!(
+                "    n{idx} [label=\"{}\", shape={shape}, tooltip=\"{:?}\"];\n",
+                dot_escape(&label),
+                position
+            ));
+        }
+
+        for (number, symbol) in self.edges() {
+            dot.push_str(&format!("    n{number} -- n{symbol};\n"));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn expand_area<const E: CoordType>(area: Area, max_height: CoordType, max_width: CoordType) -> Area {
+    let (columns, rows) = area_axes(&area).expect("area corners out of order");
+
+    let columns = columns.expand_within(E, &Interval::new(0, max_width).unwrap());
+    let rows = rows.expand_within(E, &Interval::new(0, max_height).unwrap());
 
-    (Point::new(left, top), Point::new(right, bot))
+    (Point::new(columns.start(), rows.start()), Point::new(columns.end(), rows.end()))
 }
 
 fn expand_point<const E: CoordType>(p: Point, max_height: CoordType, max_width: CoordType) -> Area {
     expand_area::<E>((p, p), max_height, max_width)
 }
 
+const NEIGHBOURHOOD_COLOUR: &str = "gold";
+const ACCEPTED_COLOUR: &str = "palegreen";
+const REJECTED_COLOUR: &str = "lightcoral";
+const SYMBOL_COLOUR: &str = "steelblue";
+
+// accepted numbers are green, rejected numbers red, symbols blue and the
+// neighbourhood each symbol accepts numbers from is shaded and outlined
+pub fn render_svg(width: CoordType, height: CoordType, entries: &[Entry]) -> Svg {
+    let mut svg = Svg::new(width as usize, height as usize);
+
+    let hiearchy = entries.iter()
+        .filter(|entry| entry.is_symbol())
+        .map(|entry| (*entry, expand_point::<1>(entry.area().0, height, width)))
+        .collect::<BoundingAreaHierarchy<_>>();
+
+    for (area, _) in hiearchy.leaves() {
+        svg.fill_area(*area, NEIGHBOURHOOD_COLOUR, 0.25)
+            .outline_area(*area, NEIGHBOURHOOD_COLOUR);
+    }
+
+    for entry in entries {
+        match *entry {
+            Entry::Number(value, left, right) => {
+                let colour = if hiearchy.contains_intersection(&(left, right)) {
+                    ACCEPTED_COLOUR
+                } else {
+                    REJECTED_COLOUR
+                };
+
+                svg.fill_area((left, right), colour, 1.0)
+                    .text(left, &value.to_string(), "black");
+            },
+            Entry::Symbol(symbol, point) => {
+                svg.fill_cell(point, SYMBOL_COLOUR)
+                    .text(point, &(symbol.as_byte() as char).to_string(), "white");
+            },
+        }
+    }
+
+    svg
+}
+
 #[aoc(day3, part1)]
-fn solver_part1((width, height, entries): &(CoordType, CoordType, Vec<Entry>)) -> u32 {
+pub fn solver_part1((width, height, entries): &(CoordType, CoordType, Vec<Entry>)) -> Result<SumType, OverflowError> {
     let (numbers, symbols) = entries.iter().copied().partition::<Vec<_>, _>(Entry::is_number);
     let numbers = numbers.into_iter().map(Entry::unwrap_number).collect::<Vec<_>>();
     let symbols = symbols.into_iter().map(Entry::unwrap_symbol).collect::<Vec<_>>();
 
     let hiearchy = symbols
         .iter()
-        .map(|&(c, p)| (c as char, expand_point::<1>(p, *height, *width)))
+        .map(|&(c, p)| (c, expand_point::<1>(p, *height, *width)))
         .collect::<BoundingAreaHierarchy<_>>();
 
-    let found_numbers = numbers.iter()
+    numbers.iter()
         .filter(|(_, a)| hiearchy.contains_intersection(a))
-        .collect::<Vec<_>>();
-
-    let mut masked_output = (0..*height).map(|_| ".".repeat(*width as usize)).collect::<Vec<_>>();
-
-    found_numbers.iter().for_each(|(n, (p, _))| {
-        let y = p.y as usize;
-        let x = p.x as usize;
-        let s = n.to_string();
-        masked_output[y].replace_range(x..x + s.len(), &s);
-    });
-
-    symbols.iter().for_each(|&(c, p)| {
-        let y = p.y as usize;
-        let x = p.x as usize;
-        unsafe {
-            masked_output[y].as_bytes_mut()[x] = c;
+        .map(|&(v, ..)| v)
+        .checked_sum()
+}
+
+#[aoc(day3, part2)]
+pub fn solver_part2((width, height, entries): &(CoordType, CoordType, Vec<Entry>)) -> Result<SumType, OverflowError> {
+    let hiearchy = BoundingAreaHierarchy::from_entries(entries);
+
+    entries.iter()
+        .filter(|entry| entry.is_symbol_kind(Symbol::Gear))
+        .map(|gear| hiearchy.numbers_adjacent_to(gear, *height, *width))
+        .filter(|numbers| numbers.len() == 2)
+        .map(|numbers| numbers.into_iter().map(|(v, _)| v).checked_product::<SumType>())
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .checked_sum()
+}
+
+// every number against every symbol, no hierarchy
+#[aoc(day3, part1, Brute)]
+pub fn solver_part1_brute((width, height, entries): &(CoordType, CoordType, Vec<Entry>)) -> Result<SumType, OverflowError> {
+    entries.iter()
+        .filter(|entry| entry.is_number())
+        .filter(|number| {
+            let area = number.bounding_box(*height, *width);
+            entries.iter().any(|other| other.is_symbol() && intersects_area(&area, &other.area()))
+        })
+        .map(|number| number.unwrap_number().0)
+        .checked_sum()
+}
+
+#[aoc(day3, part2, Brute)]
+pub fn solver_part2_brute((width, height, entries): &(CoordType, CoordType, Vec<Entry>)) -> Result<SumType, OverflowError> {
+    entries.iter()
+        .filter(|entry| entry.is_symbol_kind(Symbol::Gear))
+        .map(|gear| {
+            let area = gear.bounding_box(*height, *width);
+            entries.iter()
+                .filter(|other| other.is_number() && intersects_area(&area, &other.area()))
+                .map(|number| number.unwrap_number().0)
+                .collect::<Vec<_>>()
+        })
+        .filter(|numbers| numbers.len() == 2)
+        .map(|numbers| numbers.into_iter().checked_product::<SumType>())
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .checked_sum()
+}
+
+#[aoc(day3, part1, Graph)]
+pub fn solver_part1_graph((width, height, entries): &(CoordType, CoordType, Vec<Entry>)) -> Result<SumType, OverflowError> {
+    AdjacencyGraph::build(*width, *height, entries).part_number_sum()
+}
+
+#[aoc(day3, part2, Graph)]
+pub fn solver_part2_graph((width, height, entries): &(CoordType, CoordType, Vec<Entry>)) -> Result<SumType, OverflowError> {
+    AdjacencyGraph::build(*width, *height, entries).gear_ratio_sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const EXAMPLE: &[u8] = include_bytes!("../input/2023/example3.txt");
+
+    fn example() -> (CoordType, CoordType, Vec<Entry>) {
+        generator(EXAMPLE).unwrap()
+    }
+
+    #[test]
+    fn generator_rejects_malformed_grids() {
+        let error = |input: &[u8]| generator(input).err().map(|e| e.position);
+
+        assert_eq!(error(b"..1\n..\n..."), Some(6));
+        assert_eq!(error(b"..1\n....\n"), Some(7));
+        assert_eq!(error(b"..1\n. .\n"), Some(5));
+        assert_eq!(error(b""), Some(0));
+        assert_eq!(error(b"99999.\n......"), Some(0));
+    }
+
+    #[test]
+    fn symbol_round_trip() {
+        for byte in *b"*#+$/@" {
+            assert_eq!(Symbol::from_byte(byte).as_byte(), byte);
+        }
+
+        assert_eq!(Symbol::from(b'*'), Symbol::Gear);
+        assert_eq!(Symbol::from(b'/'), Symbol::Other(b'/'));
+    }
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(solver_part1(&example()), Ok(4361));
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(solver_part2(&example()), Ok(467835));
+    }
+
+    #[test]
+    fn numbers_adjacent_to_gears() {
+        let (width, height, entries) = example();
+        let hiearchy = BoundingAreaHierarchy::from_entries(&entries);
+
+        let numbers = hiearchy.numbers_adjacent_to_kind(Symbol::Gear, height, width)
+            .into_iter()
+            .map(|(value, _)| value)
+            .collect::<Vec<_>>();
+
+        assert_eq!(numbers, vec![467, 35, 617, 755, 598]);
+    }
+
+    #[test]
+    fn symbols_adjacent_to_number() {
+        let (width, height, entries) = example();
+        let hiearchy = BoundingAreaHierarchy::from_entries(&entries);
+
+        let number = entries.iter().find(|entry| matches!(entry, Entry::Number(633, ..))).unwrap();
+        let symbols = hiearchy.symbols_adjacent_to(number, height, width);
+
+        assert_eq!(symbols, vec![(Symbol::Hash, Point::new(6, 3))]);
+
+        let number = entries.iter().find(|entry| matches!(entry, Entry::Number(114, ..))).unwrap();
+        assert!(hiearchy.symbols_adjacent_to(number, height, width).is_empty());
+    }
+
+    #[test]
+    fn adjacency_graph_answers() {
+        let input = example();
+        let graph = AdjacencyGraph::build(input.0, input.1, &input.2);
+
+        assert_eq!(graph.part_number_sum(), solver_part1(&input));
+        assert_eq!(graph.gear_ratio_sum(), solver_part2(&input));
+    }
+
+    #[test]
+    fn adjacency_graph_structure() {
+        let (width, height, entries) = example();
+        let graph = AdjacencyGraph::build(width, height, &entries);
+
+        // 114 and 58 touch nothing, every other number touches one symbol
+        assert_eq!(graph.edge_count(), 8);
+        assert_eq!(graph.nodes_with_degree(0).count(), 2);
+        assert!(graph.edges().all(|(n, s)| graph.nodes()[n].is_number() && graph.nodes()[s].is_symbol()));
+
+        let components = graph.components();
+        assert_eq!(components.len(), 2 + 6);
+        assert_eq!(components.iter().map(Vec::len).sum::<usize>(), graph.node_count());
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("graph adjacency {"));
+        assert_eq!(dot.matches(" -- ").count(), 8);
+    }
+
+    #[test]
+    fn part1_sum_exceeds_value_type() {
+        // 66 * 999 = 65934 fits, one more pushes past u16::MAX
+        let input = "999*\n".repeat(67);
+        let (width, height, entries) = generator(input.as_bytes()).unwrap();
+
+        assert_eq!(solver_part1(&(width, height, entries)), Ok(67 * 999));
+    }
+
+    #[test]
+    fn svg_colours_entries() {
+        let (width, height, entries) = example();
+        let svg = render_svg(width, height, &entries).finish();
+
+        // 114 and 58 are the only rejected numbers
+        assert_eq!(svg.matches(&format!(r#"fill="{REJECTED_COLOUR}""#)).count(), 2);
+        assert_eq!(svg.matches(&format!(r#"fill="{ACCEPTED_COLOUR}""#)).count(), 8);
+        assert_eq!(svg.matches(&format!(r#"fill="{SYMBOL_COLOUR}""#)).count(), 6);
+    }
+
+    #[test]
+    fn hierarchy_stats() {
+        let (_, _, entries) = example();
+        let hiearchy = BoundingAreaHierarchy::from_entries(&entries);
+        let stats = hiearchy.stats();
+
+        // 10 numbers and 6 symbols
+        assert_eq!((stats.leaf_count, stats.node_count, stats.empty_count), (16, 15, 0));
+        assert_eq!((stats.depth, stats.min_leaf_depth, stats.max_leaf_depth), (5, 3, 5));
+        assert_eq!(stats.mean_leaf_depth, 4.125);
+        assert_eq!(stats.max_imbalance, 2);
+
+        let empty = BoundingAreaHierarchy::<Entry>::Empty.stats();
+        assert_eq!((empty.leaf_count, empty.empty_count, empty.depth), (0, 1, 0));
+    }
+
+    #[test]
+    fn hierarchy_export() {
+        let hiearchy = [('a', (Point::new(0, 0), Point::new(1, 1))), ('"', (Point::new(4, 4), Point::new(5, 5)))]
+            .into_iter()
+            .collect::<BoundingAreaHierarchy<_>>();
+
+        assert_eq!(
+            hiearchy.to_json(),
+            concat!(
+                r#"{"type":"node","depth":0,"area":[{"x":0,"y":0},{"x":5,"y":5}],"#,
+                r#""left":{"type":"leaf","depth":1,"area":[{"x":0,"y":0},{"x":1,"y":1}],"value":"'a'"},"#,
+                r#""right":{"type":"leaf","depth":1,"area":[{"x":4,"y":4},{"x":5,"y":5}],"value":"'\"'"}}"#,
+            )
+        );
+
+        let dot = hiearchy.to_dot();
+        assert!(dot.starts_with("digraph hierarchy {"));
+        assert_eq!(dot.matches(" -> ").count(), 2);
+        assert!(dot.contains(r#"label="'\"'"#));
+    }
+
+    fn overlaps(&(a0, a1): &Area, &(b0, b1): &Area) -> bool {
+        a0.x <= b1.x && b0.x <= a1.x && a0.y <= b1.y && b0.y <= a1.y
+    }
+
+    // small coordinates so that overlaps, degenerate areas and exact
+    // duplicates are all common
+    fn area() -> impl Strategy<Value = Area> {
+        (0u16..12, 0u16..12, 0u16..4, 0u16..4)
+            .prop_map(|(x, y, w, h)| (Point::new(x, y), Point::new(x + w, y + h)))
+    }
+
+    fn areas() -> impl Strategy<Value = Vec<Area>> {
+        prop::collection::vec(area(), 0..40).prop_flat_map(|pool| {
+            let len = pool.len();
+            // repeat entries of the pool to force duplicates
+            (Just(pool), prop::collection::vec(any::<prop::sample::Index>(), 0..len.min(10) + 1))
+        })
+        .prop_map(|(mut pool, repeats)| {
+            if !pool.is_empty() {
+                let copies = repeats.iter().map(|idx| pool[idx.index(pool.len())]).collect::<Vec<_>>();
+                pool.extend(copies);
+            }
+            pool
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn hierarchy_matches_brute_force(areas in areas(), target in area()) {
+            let hiearchy = areas.iter().copied().enumerate().collect::<BoundingAreaHierarchy<_>>();
+
+            let mut found = hiearchy.search_intersections(&target).into_iter().copied().collect::<Vec<_>>();
+            found.sort_unstable();
+
+            let expected = areas.iter()
+                .enumerate()
+                .filter(|(_, area)| overlaps(area, &target))
+                .map(|(idx, _)| idx)
+                .collect::<Vec<_>>();
+
+            prop_assert_eq!(&found, &expected);
+            prop_assert_eq!(hiearchy.contains_intersection(&target), !expected.is_empty());
+            prop_assert_eq!(hiearchy.stats().leaf_count, areas.len());
         }
-    });
 
-    masked_output.into_iter().for_each(|l| println!("{}", l));
+        #[test]
+        fn identical_areas(count in 1usize..64, target in area()) {
+            let area = (Point::new(3, 3), Point::new(3, 3));
+            let hiearchy = (0..count).map(|idx| (idx, area)).collect::<BoundingAreaHierarchy<_>>();
 
-    found_numbers.into_iter()
-        .map(|(v, ..)| v)
-        .sum::<u16>() as u32
+            let expected = if overlaps(&area, &target) { count } else { 0 };
+            prop_assert_eq!(hiearchy.search_intersections(&target).len(), expected);
+        }
+    }
 }
@@ -0,0 +1,85 @@
+// Generator output of any day as JSON, for golden-file tests and for seeing
+// what a parser made of an input. Only built with the `serde` feature.
+
+use std::{borrow::Cow, error::Error, fs, path::Path};
+
+use serde::Serialize;
+
+use crate::{day2, day3};
+
+pub fn to_json<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(value).map(|json| json + "\n")
+}
+
+// day 1 has no generator, its solvers see the non-empty lines as they are
+pub fn generated(day: u32, input: &[u8]) -> Result<String, Box<dyn Error>> {
+    Ok(match day {
+        1 => to_json(
+            &input
+                .split(|&c| c == b'\n')
+                .filter(|line| !line.is_empty())
+                .map(String::from_utf8_lossy)
+                .collect::<Vec<Cow<str>>>(),
+        )?,
+        2 => to_json(&day2::generator(input)?)?,
+        3 => to_json(&day3::generator(input)?)?,
+        _ => return Err(format!("no generator for day {day}").into()),
+    })
+}
+
+pub fn write(day: u32, input: &[u8], path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+    fs::write(path, generated(day, input)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::PathBuf};
+
+    use super::*;
+    use crate::day3::Entry;
+
+    const EXAMPLE2: &[u8] = include_bytes!("../input/2023/example2.txt");
+    const EXAMPLE3: &[u8] = include_bytes!("../input/2023/example3.txt");
+
+    fn golden_path(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("input/2023/golden").join(name)
+    }
+
+    // AOC_UPDATE_GOLDEN=1 rewrites the file instead of comparing
+    fn check_golden(day: u32, input: &[u8], name: &str) {
+        let path = golden_path(name);
+
+        if env::var_os("AOC_UPDATE_GOLDEN").is_some() {
+            write(day, input, &path).unwrap();
+        }
+
+        let expected = fs::read_to_string(&path).unwrap();
+        assert_eq!(generated(day, input).unwrap(), expected, "{} is out of date", path.display());
+    }
+
+    #[test]
+    fn golden_day2() {
+        check_golden(2, EXAMPLE2, "example2.json");
+    }
+
+    #[test]
+    fn golden_day3() {
+        check_golden(3, EXAMPLE3, "example3.json");
+    }
+
+    #[test]
+    fn golden_files_round_trip() {
+        let games: Vec<(u32, day2::Game)> = serde_json::from_str(&fs::read_to_string(golden_path("example2.json")).unwrap()).unwrap();
+        assert_eq!(games, day2::generator(EXAMPLE2).unwrap());
+
+        let schematic: (u16, u16, Vec<Entry>) = serde_json::from_str(&fs::read_to_string(golden_path("example3.json")).unwrap()).unwrap();
+        assert_eq!(schematic, day3::generator(EXAMPLE3).unwrap());
+    }
+
+    #[test]
+    fn day1_lines() {
+        assert_eq!(generated(1, b"a1\n\nb2\n").unwrap(), "[\n  \"a1\",\n  \"b2\"\n]\n");
+        assert!(generated(4, b"").is_err());
+    }
+}
@@ -0,0 +1,324 @@
+// State of the day 3 grid explorer: cursor, viewport and what is highlighted
+// around the entry under the cursor. The terminal side lives in
+// src/bin/tui.rs behind the `tui` feature; this part is plain data so it can
+// be tested without one.
+
+use crate::{
+    day3::{self, contains_point, BoundingAreaHierarchy, Entry},
+    util::{parse::ParseError, Point},
+};
+
+type Cell = Point<u16>;
+type Area = (Cell, Cell);
+
+// in order of precedence when a cell is in several
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    None,
+    // the expanded area of the selected entry
+    Neighbourhood,
+    // a hierarchy leaf intersecting that area
+    Leaf,
+    Selected,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub entry: Entry,
+    pub expanded: Area,
+    pub leaves: Vec<(Area, Entry)>,
+}
+
+pub struct Explorer {
+    width: u16,
+    height: u16,
+    rows: Vec<Vec<u8>>,
+    // index into `entries` for every cell covered by one
+    cells: Vec<Option<usize>>,
+    entries: Vec<Entry>,
+    hierarchy: BoundingAreaHierarchy<Entry>,
+    cursor: Cell,
+    // top left cell of the view
+    scroll: Cell,
+    selection: Option<Selection>,
+}
+
+impl Explorer {
+    pub fn new(input: &[u8]) -> Result<Self, ParseError> {
+        let (width, height, entries) = day3::generator(input)?;
+        let rows = input
+            .split(|&c| c == b'\n')
+            .filter(|row| !row.is_empty())
+            .map(<[u8]>::to_vec)
+            .collect();
+
+        let mut cells = vec![None; width as usize * height as usize];
+        for (idx, entry) in entries.iter().enumerate() {
+            let (upper_left, lower_right) = entry.area();
+            for y in upper_left.y..=lower_right.y {
+                for x in upper_left.x..=lower_right.x {
+                    cells[y as usize * width as usize + x as usize] = Some(idx);
+                }
+            }
+        }
+
+        let mut explorer = Self {
+            width,
+            height,
+            rows,
+            cells,
+            hierarchy: BoundingAreaHierarchy::from_entries(&entries),
+            entries,
+            cursor: Cell::new(0, 0),
+            scroll: Cell::new(0, 0),
+            selection: None,
+        };
+        explorer.select();
+
+        Ok(explorer)
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn cursor(&self) -> Cell {
+        self.cursor
+    }
+
+    pub fn scroll(&self) -> Cell {
+        self.scroll
+    }
+
+    pub fn selection(&self) -> Option<&Selection> {
+        self.selection.as_ref()
+    }
+
+    // the byte shown at `cell`
+    pub fn byte(&self, cell: Cell) -> u8 {
+        self.rows[cell.y as usize][cell.x as usize]
+    }
+
+    pub fn entry_at(&self, cell: Cell) -> Option<&Entry> {
+        self.cells[cell.y as usize * self.width as usize + cell.x as usize].map(|idx| &self.entries[idx])
+    }
+
+    // moves by (dx, dy) cells, stopping at the edges
+    pub fn move_cursor(&mut self, dx: i32, dy: i32) {
+        let clamp = |value: u16, by: i32, limit: u16| (value as i32 + by).clamp(0, limit as i32 - 1) as u16;
+
+        self.cursor = Cell::new(clamp(self.cursor.x, dx, self.width), clamp(self.cursor.y, dy, self.height));
+        self.select();
+    }
+
+    pub fn move_to(&mut self, cell: Cell) {
+        self.cursor = Cell::new(cell.x.min(self.width - 1), cell.y.min(self.height - 1));
+        self.select();
+    }
+
+    // the next entry after the cursor in reading order, wrapping around
+    pub fn next_entry(&mut self) {
+        let current = self.entry_at(self.cursor).copied();
+        let after = |entry: &Entry| entry.area().0 > self.cursor;
+
+        let next = self.entries.iter()
+            .filter(|&&entry| Some(entry) != current)
+            .find(|entry| after(entry))
+            .or_else(|| self.entries.first());
+
+        if let Some(next) = next {
+            self.move_to(next.area().0);
+        }
+    }
+
+    // scrolls just enough to keep the cursor inside a view of the given size
+    pub fn follow(&mut self, view_width: u16, view_height: u16) {
+        let follow = |scroll: u16, cursor: u16, size: u16| {
+            if cursor < scroll {
+                cursor
+            } else if size > 0 && cursor >= scroll + size {
+                cursor + 1 - size
+            } else {
+                scroll
+            }
+        };
+
+        self.scroll = Cell::new(
+            follow(self.scroll.x, self.cursor.x, view_width),
+            follow(self.scroll.y, self.cursor.y, view_height),
+        );
+    }
+
+    pub fn highlight(&self, cell: Cell) -> Highlight {
+        let Some(selection) = &self.selection else {
+            return Highlight::None;
+        };
+
+        if contains_point(&selection.entry.area(), cell) {
+            Highlight::Selected
+        } else if selection.leaves.iter().any(|(area, _)| contains_point(area, cell)) {
+            Highlight::Leaf
+        } else if contains_point(&selection.expanded, cell) {
+            Highlight::Neighbourhood
+        } else {
+            Highlight::None
+        }
+    }
+
+    fn select(&mut self) {
+        self.selection = self.entry_at(self.cursor).map(|&entry| {
+            // the same area the solvers query, which may reach one cell past
+            // the bottom and right edges
+            let expanded = entry.bounding_box(self.height, self.width);
+            let leaves = self.hierarchy
+                .intersecting_leaves(&expanded)
+                .into_iter()
+                .filter(|(_, &other)| other != entry)
+                .map(|(&area, &other)| (area, other))
+                .collect();
+
+            Selection { entry, expanded, leaves }
+        });
+    }
+
+    // one line describing the cursor and the selection
+    pub fn status(&self) -> String {
+        let position = format!("{},{}", self.cursor.x, self.cursor.y);
+
+        match &self.selection {
+            None => format!("{position}  empty"),
+            Some(selection) => {
+                let describe = |entry: &Entry| match *entry {
+                    Entry::Number(value, ..) => value.to_string(),
+                    Entry::Symbol(symbol, _) => (symbol.as_byte() as char).to_string(),
+                };
+                let leaves = selection.leaves.iter().map(|(_, entry)| describe(entry)).collect::<Vec<_>>();
+                let (upper_left, lower_right) = selection.expanded;
+
+                format!(
+                    "{position}  {}  area {upper_left}..{lower_right}  {} leaves: {}",
+                    describe(&selection.entry),
+                    leaves.len(),
+                    leaves.join(" "),
+                )
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day3::{intersects_area, Symbol};
+
+    const EXAMPLE: &[u8] = include_bytes!("../input/2023/example3.txt");
+
+    // the selection's leaves found by scanning every entry
+    fn brute_leaves(explorer: &Explorer) -> Vec<Entry> {
+        let selection = explorer.selection().unwrap();
+
+        explorer.entries.iter()
+            .filter(|&&entry| entry != selection.entry && intersects_area(&entry.area(), &selection.expanded))
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn selects_entry_under_cursor() {
+        let mut explorer = Explorer::new(EXAMPLE).unwrap();
+
+        // 467 starts at the top left
+        assert!(matches!(explorer.selection().map(|s| s.entry), Some(Entry::Number(467, ..))));
+        explorer.move_cursor(2, 0);
+        assert!(matches!(explorer.selection().map(|s| s.entry), Some(Entry::Number(467, ..))));
+        explorer.move_cursor(1, 0);
+        assert_eq!(explorer.selection(), None);
+        assert!(explorer.status().ends_with("empty"));
+    }
+
+    #[test]
+    fn leaves_around_a_gear() {
+        let mut explorer = Explorer::new(EXAMPLE).unwrap();
+        explorer.move_to(Cell::new(3, 1));
+
+        let selection = explorer.selection().unwrap().clone();
+        assert_eq!(selection.entry, Entry::Symbol(Symbol::Gear, Cell::new(3, 1)));
+        assert_eq!(selection.expanded, (Cell::new(2, 0), Cell::new(4, 2)));
+
+        let mut values = selection.leaves.iter().map(|(_, entry)| entry.unwrap_number().0).collect::<Vec<_>>();
+        values.sort();
+        assert_eq!(values, vec![35, 467]);
+
+        assert_eq!(explorer.highlight(Cell::new(3, 1)), Highlight::Selected);
+        assert_eq!(explorer.highlight(Cell::new(0, 0)), Highlight::Leaf);
+        assert_eq!(explorer.highlight(Cell::new(4, 1)), Highlight::Neighbourhood);
+        assert_eq!(explorer.highlight(Cell::new(9, 9)), Highlight::None);
+        assert!(explorer.status().contains("2 leaves"));
+    }
+
+    #[test]
+    fn expanded_area_matches_the_solvers() {
+        let mut explorer = Explorer::new(EXAMPLE).unwrap();
+        // 598 on the bottom row
+        explorer.move_to(Cell::new(5, 9));
+
+        let selection = explorer.selection().unwrap();
+        assert_eq!(selection.entry, Entry::Number(598, Cell::new(5, 9), Cell::new(7, 9)));
+        assert_eq!(selection.expanded, selection.entry.bounding_box(explorer.height(), explorer.width()));
+        assert_eq!(selection.expanded, (Cell::new(4, 8), Cell::new(8, 10)));
+    }
+
+    #[test]
+    fn leaves_match_brute_force_everywhere() {
+        let mut explorer = Explorer::new(EXAMPLE).unwrap();
+
+        for y in 0..explorer.height() {
+            for x in 0..explorer.width() {
+                explorer.move_to(Cell::new(x, y));
+
+                if let Some(selection) = explorer.selection() {
+                    let mut leaves = selection.leaves.iter().map(|&(_, entry)| entry).collect::<Vec<_>>();
+                    let mut brute = brute_leaves(&explorer);
+                    leaves.sort_by_key(|entry| entry.area());
+                    brute.sort_by_key(|entry| entry.area());
+
+                    assert_eq!(leaves, brute, "at {x},{y}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cursor_and_view() {
+        let mut explorer = Explorer::new(EXAMPLE).unwrap();
+
+        explorer.move_cursor(-5, -5);
+        assert_eq!(explorer.cursor(), Cell::new(0, 0));
+        explorer.move_cursor(50, 50);
+        assert_eq!(explorer.cursor(), Cell::new(9, 9));
+
+        explorer.follow(4, 3);
+        assert_eq!(explorer.scroll(), Cell::new(6, 7));
+        explorer.move_to(Cell::new(5, 8));
+        explorer.follow(4, 3);
+        assert_eq!(explorer.scroll(), Cell::new(5, 7));
+    }
+
+    #[test]
+    fn next_entry_in_reading_order() {
+        let mut explorer = Explorer::new(EXAMPLE).unwrap();
+
+        explorer.next_entry();
+        assert!(matches!(explorer.selection().map(|s| s.entry), Some(Entry::Number(114, ..))));
+        explorer.next_entry();
+        assert!(matches!(explorer.selection().map(|s| s.entry), Some(Entry::Symbol(Symbol::Gear, _))));
+
+        explorer.move_to(Cell::new(9, 9));
+        explorer.next_entry();
+        assert_eq!(explorer.cursor(), Cell::new(0, 0));
+    }
+}
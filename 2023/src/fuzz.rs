@@ -0,0 +1,118 @@
+// Entry points shared by the cargo-fuzz targets in `fuzz/` and the offline
+// mutation tests below. Each one runs every generator and solver of a day on
+// arbitrary bytes and discards the results: returning a value or an error is
+// fine, only a panic or a hang is a failure.
+
+use crate::{day1, day2, day3};
+
+pub fn day1(data: &[u8]) {
+    let _ = day1::solver_part1(data);
+    let _ = day1::solver_part1_swar(data);
+    let _ = day1::solver_part2(data);
+    let _ = day1::solver_part2_scan(data);
+}
+
+pub fn day2(data: &[u8]) {
+    if let Ok(games) = day2::generator(data) {
+        let _ = day2::part1_solver(&games);
+        let _ = day2::part2_solver(&games);
+    }
+}
+
+pub fn day3(data: &[u8]) {
+    if let Ok(schematic) = day3::generator(data) {
+        let (width, height, entries) = &schematic;
+
+        let _ = day3::solver_part1(&schematic);
+        let _ = day3::solver_part2(&schematic);
+        let _ = day3::solver_part1_brute(&schematic);
+        let _ = day3::solver_part2_brute(&schematic);
+
+        let graph = day3::AdjacencyGraph::build(*width, *height, entries);
+        let _ = graph.part_number_sum();
+        let _ = graph.gear_ratio_sum();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    // the same seeds `fuzz/` is pointed at
+    const DAY1: &[u8] = include_bytes!("../input/2023/day1.txt");
+    const DAY2: &[u8] = include_bytes!("../input/2023/day2.txt");
+    const DAY3: &[u8] = include_bytes!("../input/2023/day3.txt");
+    const EXAMPLE3: &[u8] = include_bytes!("../input/2023/example3.txt");
+
+    #[derive(Debug, Clone)]
+    enum Mutation {
+        Replace(prop::sample::Index, u8),
+        Insert(prop::sample::Index, u8),
+        Remove(prop::sample::Index),
+        Truncate(prop::sample::Index),
+    }
+
+    // bytes that are meaningful to at least one grammar, plus anything else
+    fn byte() -> impl Strategy<Value = u8> {
+        prop_oneof![
+            3 => prop::sample::select(b"0123456789.*#+$\n ,;:Gameredgreenblueonetwothreefoursixseveneightnine".to_vec()),
+            1 => any::<u8>(),
+        ]
+    }
+
+    fn mutation() -> impl Strategy<Value = Mutation> {
+        prop_oneof![
+            4 => (any::<prop::sample::Index>(), byte()).prop_map(|(idx, byte)| Mutation::Replace(idx, byte)),
+            4 => (any::<prop::sample::Index>(), byte()).prop_map(|(idx, byte)| Mutation::Insert(idx, byte)),
+            4 => any::<prop::sample::Index>().prop_map(Mutation::Remove),
+            1 => any::<prop::sample::Index>().prop_map(Mutation::Truncate),
+        ]
+    }
+
+    fn mutated(seeds: &'static [&'static [u8]]) -> impl Strategy<Value = Vec<u8>> {
+        (prop::sample::select(seeds), prop::collection::vec(mutation(), 0..8)).prop_map(|(seed, mutations)| {
+            let mut data = seed.to_vec();
+
+            for mutation in mutations {
+                let len = data.len();
+                match mutation {
+                    Mutation::Replace(idx, byte) if len > 0 => data[idx.index(len)] = byte,
+                    Mutation::Insert(idx, byte) => data.insert(idx.index(len + 1), byte),
+                    Mutation::Remove(idx) if len > 0 => {
+                        data.remove(idx.index(len));
+                    },
+                    Mutation::Truncate(idx) => data.truncate(idx.index(len + 1)),
+                    _ => (),
+                }
+            }
+
+            data
+        })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn day1_never_panics(data in mutated(&[DAY1])) {
+            super::day1(&data);
+        }
+
+        #[test]
+        fn day2_never_panics(data in mutated(&[DAY2])) {
+            super::day2(&data);
+        }
+
+        #[test]
+        fn day3_never_panics(data in mutated(&[DAY3, EXAMPLE3])) {
+            super::day3(&data);
+        }
+
+        #[test]
+        fn arbitrary_bytes_never_panic(data in prop::collection::vec(byte(), 0..256)) {
+            super::day1(&data);
+            super::day2(&data);
+            super::day3(&data);
+        }
+    }
+}
@@ -0,0 +1,337 @@
+// Random but valid puzzle inputs for days 1-3, reproducible from a seed, for
+// benchmarks and differential tests at sizes the real inputs never reach.
+
+use std::io::{self, Write};
+
+use crate::day1::DIGIT_WORDS;
+
+// SplitMix64, small and good enough for test data
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform in 0..n, n must be positive
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "empty range");
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    // uniform in low..=high
+    pub fn between(&mut self, low: usize, high: usize) -> usize {
+        low + self.below((high - low) as u64 + 1) as usize
+    }
+
+    // true with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+fn collect(write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> Vec<u8> {
+    let mut output = Vec::new();
+    write(&mut output).expect("writing to a Vec cannot fail");
+    output
+}
+
+const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+// lines of letters, digits and digit words, each with at least one digit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Day1 {
+    pub lines: usize,
+    pub min_length: usize,
+    pub max_length: usize,
+    // per position, chance of a digit word and of a digit
+    pub word_density: f64,
+    pub digit_density: f64,
+}
+
+impl Default for Day1 {
+    fn default() -> Self {
+        Self { lines: 1000, min_length: 5, max_length: 50, word_density: 0.05, digit_density: 0.05 }
+    }
+}
+
+impl Day1 {
+    pub fn with_lines(self, lines: usize) -> Self {
+        Self { lines, ..self }
+    }
+
+    pub fn with_length(self, min_length: usize, max_length: usize) -> Self {
+        assert!(min_length <= max_length, "min_length above max_length");
+        Self { min_length, max_length, ..self }
+    }
+
+    pub fn with_densities(self, word_density: f64, digit_density: f64) -> Self {
+        Self { word_density, digit_density, ..self }
+    }
+
+    pub fn write(&self, seed: u64, mut output: impl Write) -> io::Result<()> {
+        let mut rng = Rng::new(seed);
+        let mut line = Vec::new();
+
+        for _ in 0..self.lines {
+            let length = rng.between(self.min_length, self.max_length);
+            line.clear();
+
+            while line.len() < length {
+                if rng.chance(self.word_density) {
+                    line.extend_from_slice(rng.pick(&DIGIT_WORDS));
+                } else if rng.chance(self.digit_density) {
+                    line.push(b'1' + rng.below(9) as u8);
+                } else {
+                    line.push(*rng.pick(LETTERS));
+                }
+            }
+
+            // part 1 needs a digit on every line
+            if !line.iter().any(u8::is_ascii_digit) {
+                let at = rng.between(0, line.len());
+                line.insert(at, b'1' + rng.below(9) as u8);
+            }
+
+            output.write_all(&line)?;
+            output.write_all(b"\n")?;
+        }
+
+        Ok(())
+    }
+
+    pub fn generate(&self, seed: u64) -> Vec<u8> {
+        collect(|output| self.write(seed, output))
+    }
+}
+
+// games numbered from 1, every colour at most once per hand
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Day2 {
+    pub games: usize,
+    pub max_hands: usize,
+    // cubes of one colour in a hand are drawn from 1..=max_count
+    pub max_count: u32,
+}
+
+impl Default for Day2 {
+    fn default() -> Self {
+        Self { games: 100, max_hands: 6, max_count: 20 }
+    }
+}
+
+impl Day2 {
+    pub fn with_games(self, games: usize) -> Self {
+        Self { games, ..self }
+    }
+
+    pub fn with_max_hands(self, max_hands: usize) -> Self {
+        assert!(max_hands > 0, "a game needs a hand");
+        Self { max_hands, ..self }
+    }
+
+    pub fn with_max_count(self, max_count: u32) -> Self {
+        assert!(max_count > 0, "a colour is drawn at least once");
+        Self { max_count, ..self }
+    }
+
+    pub fn write(&self, seed: u64, mut output: impl Write) -> io::Result<()> {
+        let mut rng = Rng::new(seed);
+
+        for id in 1..=self.games {
+            write!(output, "Game {id}: ")?;
+
+            for hand in 0..rng.between(1, self.max_hands) {
+                if hand > 0 {
+                    output.write_all(b"; ")?;
+                }
+
+                // a non-empty subset of the colours in random order
+                let mut colours = ["red", "green", "blue"];
+                for i in (1..colours.len()).rev() {
+                    colours.swap(i, rng.between(0, i));
+                }
+                let drawn = rng.between(1, colours.len());
+
+                for (idx, colour) in colours[..drawn].iter().enumerate() {
+                    if idx > 0 {
+                        output.write_all(b", ")?;
+                    }
+                    write!(output, "{} {colour}", 1 + rng.below(self.max_count as u64))?;
+                }
+            }
+
+            output.write_all(b"\n")?;
+        }
+
+        Ok(())
+    }
+
+    pub fn generate(&self, seed: u64) -> Vec<u8> {
+        collect(|output| self.write(seed, output))
+    }
+}
+
+const SYMBOLS: &[u8] = b"*#+$/@=%&-";
+
+// a rectangular grid of dots, numbers of one to three digits and symbols;
+// numbers never touch horizontally so they cannot merge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Day3 {
+    pub width: u16,
+    pub height: u16,
+    // per cell, chance of starting a number and of a symbol
+    pub number_density: f64,
+    pub symbol_density: f64,
+    // share of symbols that are gears
+    pub gear_share: f64,
+}
+
+impl Default for Day3 {
+    fn default() -> Self {
+        Self { width: 140, height: 140, number_density: 0.08, symbol_density: 0.05, gear_share: 0.3 }
+    }
+}
+
+impl Day3 {
+    pub fn with_size(self, width: u16, height: u16) -> Self {
+        assert!(width > 0 && height > 0, "empty grid");
+        Self { width, height, ..self }
+    }
+
+    pub fn with_densities(self, number_density: f64, symbol_density: f64) -> Self {
+        Self { number_density, symbol_density, ..self }
+    }
+
+    pub fn with_gear_share(self, gear_share: f64) -> Self {
+        Self { gear_share, ..self }
+    }
+
+    pub fn write(&self, seed: u64, mut output: impl Write) -> io::Result<()> {
+        let mut rng = Rng::new(seed);
+        let width = self.width as usize;
+        let mut row = Vec::with_capacity(width + 1);
+
+        for _ in 0..self.height {
+            row.clear();
+
+            while row.len() < width {
+                let digits = rng.between(1, 3);
+
+                if row.len() + digits <= width && rng.chance(self.number_density) {
+                    row.push(b'1' + rng.below(9) as u8);
+                    (1..digits).for_each(|_| row.push(b'0' + rng.below(10) as u8));
+
+                    // keep the next number apart
+                    if row.len() < width {
+                        row.push(b'.');
+                    }
+                } else if rng.chance(self.symbol_density) {
+                    row.push(if rng.chance(self.gear_share) { b'*' } else { *rng.pick(SYMBOLS) });
+                } else {
+                    row.push(b'.');
+                }
+            }
+
+            row.push(b'\n');
+            output.write_all(&row)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn generate(&self, seed: u64) -> Vec<u8> {
+        collect(|output| self.write(seed, output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{day1, day2, day3, runner};
+
+    #[test]
+    fn rng_ranges() {
+        let mut rng = super::Rng::new(7);
+
+        for _ in 0..1000 {
+            assert!(rng.below(10) < 10);
+            assert!((3..=5).contains(&rng.between(3, 5)));
+        }
+        assert!((0..1000).all(|_| !rng.chance(0.0)));
+        assert!((0..1000).all(|_| rng.chance(1.0)));
+    }
+
+    #[test]
+    fn seeds_are_reproducible() {
+        assert_eq!(Day3::default().generate(1), Day3::default().generate(1));
+        assert_ne!(Day3::default().generate(1), Day3::default().generate(2));
+    }
+
+    #[test]
+    fn sizes() {
+        let (width, height, _) = day3::generator(&Day3::default().with_size(300, 20).generate(3)).unwrap();
+        assert_eq!((width, height), (300, 20));
+
+        let games = day2::generator(&Day2::default().with_games(500).generate(3)).unwrap();
+        assert_eq!(games.len(), 500);
+        assert!(games.iter().enumerate().all(|(idx, &(id, _))| id as usize == idx + 1));
+
+        let lines = Day1::default().with_lines(50).with_length(10, 10).generate(3);
+        assert_eq!(lines.split(|&c| c == b'\n').filter(|line| !line.is_empty()).count(), 50);
+    }
+
+    #[test]
+    fn dense_grids_stay_valid() {
+        let input = Day3::default().with_size(64, 64).with_densities(1.0, 1.0).generate(5);
+
+        assert!(day3::generator(&input).is_ok());
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn day1_inputs_are_solvable(seed in any::<u64>(), lines in 1usize..50, words in 0.0..0.5, digits in 0.0..0.5) {
+            let input = Day1::default().with_lines(lines).with_densities(words, digits).generate(seed);
+
+            prop_assert!(day1::solver_part1(&input).is_ok());
+            prop_assert!(day1::solver_part2(&input).is_ok());
+            prop_assert!(runner::compare(1, 1, &input).is_ok());
+            prop_assert!(runner::compare(1, 2, &input).is_ok());
+        }
+
+        #[test]
+        fn day2_inputs_parse(seed in any::<u64>(), games in 1usize..50, hands in 1usize..8, count in 1u32..30) {
+            let input = Day2::default().with_games(games).with_max_hands(hands).with_max_count(count).generate(seed);
+
+            prop_assert!(day2::generator(&input).is_ok());
+        }
+
+        #[test]
+        fn day3_variants_agree(seed in any::<u64>(), width in 1u16..60, height in 1u16..60, numbers in 0.0..0.5, symbols in 0.0..0.5) {
+            let input = Day3::default().with_size(width, height).with_densities(numbers, symbols).generate(seed);
+
+            prop_assert!(runner::compare(3, 1, &input).is_ok());
+            prop_assert!(runner::compare(3, 2, &input).is_ok());
+        }
+    }
+}
@@ -0,0 +1,3 @@
+// Puzzle inputs that are not committed yet; `fetch` downloads and caches them.
+
+pub mod fetch;
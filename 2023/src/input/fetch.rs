@@ -0,0 +1,568 @@
+// Downloads a day's input with the session token of a logged in browser and
+// caches it next to the committed ones:
+//
+//     AOC_SESSION=... cargo run --bin runner -- fetch 4
+//
+// a day is fetched at most once. Downloads have their checksum recorded in
+// `checksums.txt` and are verified against it on later reads; `record` adds
+// the checksums of files committed by hand. AOC_BASE_URL points the client
+// at another server, e.g. a mirror or a local stub.
+
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    env,
+    error::Error,
+    fmt::Display,
+    fs,
+    io::{self, Read, Write},
+    net::{Ipv6Addr, TcpStream},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const YEAR: u32 = 2023;
+const CHECKSUMS: &str = "checksums.txt";
+const USER_AGENT: &str = concat!("aoc-2023-runner/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug)]
+pub enum FetchError {
+    Io(io::Error),
+    // nothing cached and no token to fetch it with
+    MissingSession { day: u32 },
+    // tokens end up in request headers, so only plain ones are accepted
+    InvalidSession,
+    UnsupportedUrl(String),
+    // the request could not be made at all
+    Transport(String),
+    MalformedResponse(&'static str),
+    Status { code: u16, body: String },
+    Corrupt { path: PathBuf, expected: String, found: String },
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{error}"),
+            Self::MissingSession { day } => write!(f, "day {day} is not cached and AOC_SESSION is not set"),
+            Self::InvalidSession => write!(f, "the session token may only contain letters and digits"),
+            Self::UnsupportedUrl(url) => write!(f, "unsupported base url {url}, expected http:// or https://"),
+            Self::Transport(error) => write!(f, "request failed: {error}"),
+            Self::MalformedResponse(reason) => write!(f, "malformed response: {reason}"),
+            Self::Status { code, body } => write!(f, "server answered {code}: {body}"),
+            Self::Corrupt { path, expected, found } => write!(
+                f,
+                "{} does not match its checksum (expected {expected}, found {found}), delete it to fetch it again",
+                path.display(),
+            ),
+        }
+    }
+}
+
+impl Error for FetchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FetchError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+// FNV-1a; enough to notice a truncated or hand edited cache file
+pub fn checksum(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3));
+
+    format!("{hash:016x}")
+}
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+    cache_dir: PathBuf,
+    timeout: Duration,
+}
+
+impl Client {
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: None,
+            cache_dir: cache_dir.into(),
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    // caches into input/2023 of this crate, where cargo-aoc looks as well;
+    // AOC_SESSION and AOC_BASE_URL are used when set
+    pub fn from_env() -> Self {
+        let var = |name| env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let mut client = Self::new(Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("input/{YEAR}")));
+
+        if let Some(base_url) = var("AOC_BASE_URL") {
+            client = client.with_base_url(base_url);
+        }
+        if let Some(session) = var("AOC_SESSION") {
+            client = client.with_session(session);
+        }
+
+        client
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_session(mut self, session: impl Into<String>) -> Self {
+        self.session = Some(session.into().trim().to_string());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn path(&self, day: u32) -> PathBuf {
+        self.cache_dir.join(format!("day{day}.txt"))
+    }
+
+    pub fn is_cached(&self, day: u32) -> bool {
+        self.path(day).is_file()
+    }
+
+    // the cached input, downloading it first if there is none; a cached file
+    // is only checked against its checksum, files without one are left alone
+    pub fn fetch(&self, day: u32) -> Result<Vec<u8>, FetchError> {
+        let path = self.path(day);
+        let name = format!("day{day}.txt");
+        let mut checksums = self.read_checksums()?;
+
+        match fs::read(&path) {
+            Ok(input) => {
+                let found = checksum(&input);
+
+                return match checksums.get(&name) {
+                    Some(expected) if *expected != found => Err(FetchError::Corrupt { path, expected: expected.clone(), found }),
+                    _ => Ok(input),
+                };
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => (),
+            Err(error) => return Err(error.into()),
+        }
+
+        let input = self.download(day)?;
+
+        // written aside and renamed so an interrupted write is never cached
+        fs::create_dir_all(&self.cache_dir)?;
+        let partial = path.with_extension("txt.part");
+        fs::write(&partial, &input)?;
+        fs::rename(&partial, &path)?;
+
+        checksums.insert(name, checksum(&input));
+        self.write_checksums(&checksums)?;
+
+        Ok(input)
+    }
+
+    // like `fetch`, and also records the checksum of a cached file that has
+    // none, e.g. one committed by hand before there were checksums
+    pub fn record(&self, day: u32) -> Result<Vec<u8>, FetchError> {
+        let input = self.fetch(day)?;
+        let name = format!("day{day}.txt");
+        let mut checksums = self.read_checksums()?;
+
+        if let Entry::Vacant(entry) = checksums.entry(name) {
+            entry.insert(checksum(&input));
+            self.write_checksums(&checksums)?;
+        }
+
+        Ok(input)
+    }
+
+    fn download(&self, day: u32) -> Result<Vec<u8>, FetchError> {
+        let session = self.session.as_deref().ok_or(FetchError::MissingSession { day })?;
+        if session.is_empty() || !session.bytes().all(|byte| byte.is_ascii_alphanumeric()) {
+            return Err(FetchError::InvalidSession);
+        }
+
+        let url = format!("{}/{YEAR}/day/{day}/input", self.base_url);
+        let (code, body) = if let Some(location) = url.strip_prefix("http://") {
+            http_get(location, session, self.timeout)?
+        } else if url.starts_with("https://") {
+            curl_get(&url, session, self.timeout)?
+        } else {
+            return Err(FetchError::UnsupportedUrl(self.base_url.clone()));
+        };
+
+        if code == 200 {
+            Ok(body)
+        } else {
+            let body = String::from_utf8_lossy(&body);
+            Err(FetchError::Status { code, body: body.trim().lines().next().unwrap_or("").to_string() })
+        }
+    }
+
+    // file name to checksum, one "<checksum>  <file>" line each
+    fn read_checksums(&self) -> Result<BTreeMap<String, String>, FetchError> {
+        let text = match fs::read_to_string(self.cache_dir.join(CHECKSUMS)) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        text.lines()
+            .map(|line| {
+                let (checksum, name) = line.split_once("  ").ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("bad line in {CHECKSUMS}: {line}"))
+                })?;
+                Ok((name.to_string(), checksum.to_string()))
+            })
+            .collect()
+    }
+
+    fn write_checksums(&self, checksums: &BTreeMap<String, String>) -> Result<(), FetchError> {
+        let text = checksums.iter().map(|(name, checksum)| format!("{checksum}  {name}\n")).collect::<String>();
+
+        Ok(fs::write(self.cache_dir.join(CHECKSUMS), text)?)
+    }
+}
+
+// "host[:port]" or "[ipv6][:port]" as something to connect to, port 80 by
+// default; `None` for anything else, e.g. an unbracketed ipv6 address
+fn socket_address(authority: &str) -> Option<String> {
+    let (host, port) = match authority.strip_prefix('[') {
+        Some(rest) => {
+            let (ip, port) = rest.split_once(']')?;
+            ip.parse::<Ipv6Addr>().ok()?;
+            (format!("[{ip}]"), port)
+        },
+        None => {
+            let split = authority.find(':').unwrap_or(authority.len());
+            let (host, port) = authority.split_at(split);
+            if host.is_empty() || port.matches(':').count() > 1 {
+                return None;
+            }
+            (host.to_string(), port)
+        },
+    };
+
+    let port = match port {
+        "" => 80,
+        port => port.strip_prefix(':')?.parse::<u16>().ok()?,
+    };
+
+    Some(format!("{host}:{port}"))
+}
+
+// plain HTTP/1.1 over a fresh connection; `location` is the url without
+// its scheme
+fn http_get(location: &str, session: &str, timeout: Duration) -> Result<(u16, Vec<u8>), FetchError> {
+    let (authority, path) = location.split_at(location.find('/').unwrap_or(location.len()));
+    let address = socket_address(authority).ok_or_else(|| FetchError::UnsupportedUrl(format!("http://{authority}")))?;
+
+    let transport = |error: io::Error| FetchError::Transport(format!("{address}: {error}"));
+    let mut stream = TcpStream::connect(&address).map_err(transport)?;
+    stream.set_read_timeout(Some(timeout)).map_err(transport)?;
+    stream.set_write_timeout(Some(timeout)).map_err(transport)?;
+
+    let request = format!(
+        "GET {path} HTTP/1.1\r\nHost: {authority}\r\nCookie: session={session}\r\nUser-Agent: {USER_AGENT}\r\nConnection: close\r\n\r\n"
+    );
+    stream.write_all(request.as_bytes()).map_err(transport)?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).map_err(transport)?;
+
+    parse_response(&response)
+}
+
+// fractional seconds; curl reads 0 as no limit, so nothing rounds down to it
+fn curl_max_time(timeout: Duration) -> String {
+    format!("{:.3}", timeout.as_secs_f64().max(0.001))
+}
+
+// std has no TLS; curl does, and takes the cookie on stdin so the token
+// stays out of the process list
+fn curl_get(url: &str, session: &str, timeout: Duration) -> Result<(u16, Vec<u8>), FetchError> {
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--show-error", "--config", "-", "--max-time", &curl_max_time(timeout)])
+        .args(["--write-out", "\n%{http_code}", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| FetchError::Transport(format!("could not run curl: {error}")))?;
+
+    let config = format!("header = \"Cookie: session={session}\"\nuser-agent = \"{USER_AGENT}\"\n");
+    curl.stdin.take().expect("stdin is piped").write_all(config.as_bytes())?;
+    let output = curl.wait_with_output()?;
+
+    if !output.status.success() {
+        return Err(FetchError::Transport(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+
+    // the status code is the last line, after the body
+    let split = output.stdout.iter().rposition(|&byte| byte == b'\n').ok_or(FetchError::MalformedResponse("no status code"))?;
+    let code = std::str::from_utf8(&output.stdout[split + 1..])
+        .ok()
+        .and_then(|code| code.parse().ok())
+        .ok_or(FetchError::MalformedResponse("no status code"))?;
+
+    Ok((code, output.stdout[..split].to_vec()))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+// status code and body of a complete response
+fn parse_response(response: &[u8]) -> Result<(u16, Vec<u8>), FetchError> {
+    let end = find(response, b"\r\n\r\n").ok_or(FetchError::MalformedResponse("no end of headers"))?;
+    let head = std::str::from_utf8(&response[..end]).map_err(|_| FetchError::MalformedResponse("headers are not utf-8"))?;
+    let body = &response[end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let code = lines
+        .next()
+        .and_then(|status| status.split(' ').nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or(FetchError::MalformedResponse("bad status line"))?;
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim()))
+        .collect::<Vec<_>>();
+    let header = |name: &str| headers.iter().find(|(header, _)| header == name).map(|&(_, value)| value);
+
+    let body = if header("transfer-encoding").is_some_and(|encoding| encoding.eq_ignore_ascii_case("chunked")) {
+        dechunk(body)?
+    } else if let Some(length) = header("content-length") {
+        let length = length.parse::<usize>().map_err(|_| FetchError::MalformedResponse("bad content-length"))?;
+        body.get(..length).ok_or(FetchError::MalformedResponse("body shorter than content-length"))?.to_vec()
+    } else {
+        body.to_vec()
+    };
+
+    Ok((code, body))
+}
+
+fn dechunk(mut body: &[u8]) -> Result<Vec<u8>, FetchError> {
+    let mut decoded = Vec::new();
+
+    loop {
+        let line_end = find(body, b"\r\n").ok_or(FetchError::MalformedResponse("truncated chunk"))?;
+        let size = std::str::from_utf8(&body[..line_end])
+            .ok()
+            .and_then(|line| usize::from_str_radix(line.split(';').next()?.trim(), 16).ok())
+            .ok_or(FetchError::MalformedResponse("bad chunk size"))?;
+        body = &body[line_end + 2..];
+
+        if size == 0 {
+            return Ok(decoded);
+        }
+
+        let chunk = body.get(..size).ok_or(FetchError::MalformedResponse("truncated chunk"))?;
+        decoded.extend_from_slice(chunk);
+        body = body.get(size + 2..).ok_or(FetchError::MalformedResponse("truncated chunk"))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, process, thread::{self, JoinHandle}};
+
+    use super::*;
+
+    fn response(code: u16, body: &str) -> Vec<u8> {
+        format!("HTTP/1.1 {code} Whatever\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{body}", body.len()).into_bytes()
+    }
+
+    // answers one connection per response in order, then stops listening;
+    // joining gives back the requests it saw
+    fn stub(responses: Vec<Vec<u8>>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = Vec::new();
+                    let mut buffer = [0; 1024];
+
+                    while find(&request, b"\r\n\r\n").is_none() {
+                        let read = stream.read(&mut buffer).unwrap();
+                        assert!(read > 0, "request ended early");
+                        request.extend_from_slice(&buffer[..read]);
+                    }
+                    stream.write_all(&response).unwrap();
+
+                    String::from_utf8(request).unwrap()
+                })
+                .collect()
+        });
+
+        (base_url, server)
+    }
+
+    // an empty cache directory unique to the test
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn fetches_once_then_reads_the_cache() {
+        let dir = cache_dir("once");
+        let (base_url, server) = stub(vec![response(200, "467..114..\n")]);
+        let client = Client::new(&dir).with_base_url(base_url).with_session("abc123");
+
+        assert!(!client.is_cached(3));
+        assert_eq!(client.fetch(3).unwrap(), b"467..114..\n");
+        // the stub is gone after one request, so this can only be the cache
+        assert_eq!(client.fetch(3).unwrap(), b"467..114..\n");
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2023/day/3/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=abc123\r\n"));
+
+        let checksums = fs::read_to_string(dir.join(CHECKSUMS)).unwrap();
+        assert_eq!(checksums, format!("{}  day3.txt\n", checksum(b"467..114..\n")));
+        assert!(!dir.join("day3.txt.part").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn base_url_may_have_a_path() {
+        let dir = cache_dir("path");
+        let (base_url, server) = stub(vec![response(200, "1abc2\n")]);
+        let client = Client::new(&dir).with_base_url(format!("{base_url}/mirror/")).with_session("abc123");
+
+        assert_eq!(client.fetch(1).unwrap(), b"1abc2\n");
+        assert!(server.join().unwrap()[0].starts_with("GET /mirror/2023/day/1/input "));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failures_are_not_cached() {
+        let dir = cache_dir("failure");
+        let (base_url, server) = stub(vec![response(404, "Please don't repeatedly request this endpoint before it unlocks!\n")]);
+        let client = Client::new(&dir).with_base_url(base_url).with_session("abc123");
+
+        let error = client.fetch(25).unwrap_err();
+        assert!(matches!(&error, FetchError::Status { code: 404, body } if body.starts_with("Please don't")));
+        assert!(!client.is_cached(25));
+        server.join().unwrap();
+
+        assert!(matches!(Client::new(&dir).fetch(25), Err(FetchError::MissingSession { day: 25 })));
+        assert!(matches!(Client::new(&dir).with_session("a\r\nb").fetch(25), Err(FetchError::InvalidSession)));
+        assert!(matches!(
+            Client::new(&dir).with_base_url("ftp://example.com").with_session("abc").fetch(25),
+            Err(FetchError::UnsupportedUrl(_))
+        ));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn existing_files_are_read_as_is_until_recorded() {
+        let dir = cache_dir("verify");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day2.txt"), "Game 1: 3 blue\n").unwrap();
+        let client = Client::new(&dir);
+
+        // no session needed, and reading records nothing
+        assert_eq!(client.fetch(2).unwrap(), b"Game 1: 3 blue\n");
+        assert!(!dir.join(CHECKSUMS).exists());
+
+        assert_eq!(client.record(2).unwrap(), b"Game 1: 3 blue\n");
+        assert!(fs::read_to_string(dir.join(CHECKSUMS)).unwrap().ends_with("  day2.txt\n"));
+
+        fs::write(dir.join("day2.txt"), "Game 1: 4 blue\n").unwrap();
+        let error = client.fetch(2).unwrap_err();
+        assert!(matches!(error, FetchError::Corrupt { .. }));
+        assert!(error.to_string().contains("delete it to fetch it again"));
+        assert!(matches!(client.record(2), Err(FetchError::Corrupt { .. })));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn responses() {
+        let chunked = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nabcd\r\n3;x=y\r\nefg\r\n0\r\n\r\n";
+        assert_eq!(parse_response(chunked).unwrap(), (200, b"abcdefg".to_vec()));
+
+        let unframed = b"HTTP/1.0 500 Internal Server Error\r\n\r\noops";
+        assert_eq!(parse_response(unframed).unwrap(), (500, b"oops".to_vec()));
+
+        assert!(matches!(parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\nshort"), Err(FetchError::MalformedResponse(_))));
+        assert!(matches!(parse_response(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nff\r\nab"), Err(FetchError::MalformedResponse(_))));
+        assert!(matches!(parse_response(b"garbage"), Err(FetchError::MalformedResponse(_))));
+    }
+
+    #[test]
+    fn socket_addresses() {
+        assert_eq!(socket_address("example.com").as_deref(), Some("example.com:80"));
+        assert_eq!(socket_address("127.0.0.1:8080").as_deref(), Some("127.0.0.1:8080"));
+        assert_eq!(socket_address("[::1]:8080").as_deref(), Some("[::1]:8080"));
+        assert_eq!(socket_address("[::1]").as_deref(), Some("[::1]:80"));
+
+        for unsupported in ["::1", "::1:8080", "[::1", "[nope]:80", "[::1]8080", "host:", "host:port", ":80", "host:1:2"] {
+            assert_eq!(socket_address(unsupported), None, "{unsupported}");
+        }
+    }
+
+    #[test]
+    fn ipv6_base_urls() {
+        let dir = cache_dir("ipv6");
+
+        // not every sandbox has a loopback ipv6 address
+        if let Ok(listener) = TcpListener::bind("[::1]:0") {
+            let base_url = format!("http://[::1]:{}", listener.local_addr().unwrap().port());
+            let server = thread::spawn(move || {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 1024];
+                let read = stream.read(&mut request).unwrap();
+                stream.write_all(&response(200, "Card 1: 1 | 1\n")).unwrap();
+                String::from_utf8_lossy(&request[..read]).into_owned()
+            });
+
+            let client = Client::new(&dir).with_base_url(&base_url).with_session("abc123");
+            assert_eq!(client.fetch(4).unwrap(), b"Card 1: 1 | 1\n");
+            assert!(server.join().unwrap().contains(&format!("\r\nHost: {}\r\n", &base_url["http://".len()..])));
+        }
+
+        let client = Client::new(&dir).with_base_url("http://::1:8080").with_session("abc123");
+        assert!(matches!(client.fetch(5), Err(FetchError::UnsupportedUrl(_))));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn curl_timeouts_keep_fractions() {
+        assert_eq!(curl_max_time(Duration::from_secs(30)), "30.000");
+        assert_eq!(curl_max_time(Duration::from_millis(250)), "0.250");
+        assert_eq!(curl_max_time(Duration::from_micros(10)), "0.001");
+        assert_eq!(curl_max_time(Duration::ZERO), "0.001");
+    }
+
+    #[test]
+    fn checksums_are_stable() {
+        assert_eq!(checksum(b""), "cbf29ce484222325");
+        assert_eq!(checksum(b"a"), "af63dc4c8601ec8c");
+    }
+}
@@ -5,6 +5,16 @@ pub mod day1;
 pub mod day2;
 pub mod day3;
 
+#[cfg(feature = "serde")]
+pub mod dump;
+pub mod explore;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod fuzz;
+pub mod gen;
+pub mod input;
+pub mod runner;
+
 pub mod util;
+pub mod watch;
 
 aoc_lib! {year = 2023}
@@ -0,0 +1,326 @@
+// Every solver variant registered with `#[aoc(dayN, partM, Name)]`, so
+// alternative approaches can be run side by side on the same generator
+// output. The unnamed variant is called "default".
+
+use std::{
+    error::Error,
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::{day1, day2, day3};
+
+pub type Answer = Result<String, String>;
+
+type Variant<G> = (&'static str, fn(&G) -> Answer);
+
+// every (day, part) with at least one variant
+pub const PARTS: [(u32, u32); 6] = [(1, 1), (1, 2), (2, 1), (2, 2), (3, 1), (3, 2)];
+
+fn answer<T: Display, E: Display>(result: Result<T, E>) -> Answer {
+    result.map(|value| value.to_string()).map_err(|error| error.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    pub name: &'static str,
+    pub answer: Answer,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    pub day: u32,
+    pub part: u32,
+    pub generator: Duration,
+    pub variants: Vec<Timing>,
+}
+
+impl Comparison {
+    pub fn agree(&self) -> bool {
+        self.variants.windows(2).all(|pair| pair[0].answer == pair[1].answer)
+    }
+
+    // the default variant's answer
+    pub fn answer(&self) -> &Answer {
+        &self.variants[0].answer
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name_width = self.variants.iter().map(|timing| timing.name.len()).max().unwrap_or(0).max("generator".len());
+
+        writeln!(f, "day {} part {}", self.day, self.part)?;
+        writeln!(f, "  {:name_width$}  {:>12?}", "generator", self.generator)?;
+        for timing in &self.variants {
+            let answer = match &timing.answer {
+                Ok(answer) => answer.clone(),
+                Err(error) => format!("error: {error}"),
+            };
+            writeln!(f, "  {:name_width$}  {:>12?}  {answer}", timing.name, timing.elapsed)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompareError {
+    UnknownPart { day: u32, part: u32 },
+    Generator(String),
+    Disagreement(Comparison),
+}
+
+impl Display for CompareError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownPart { day, part } => write!(f, "no solvers for day {day} part {part}"),
+            Self::Generator(error) => write!(f, "generator failed: {error}"),
+            Self::Disagreement(comparison) => write!(f, "variants disagree\n{comparison}"),
+        }
+    }
+}
+
+impl Error for CompareError {}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let value = f();
+    (value, start.elapsed())
+}
+
+// at most `limit` variants are run, the default first
+fn run<'a, G, E: Display>(
+    (day, part): (u32, u32),
+    input: &'a [u8],
+    generator: impl FnOnce(&'a [u8]) -> Result<G, E>,
+    variants: &[Variant<G>],
+    limit: usize,
+) -> Result<Comparison, CompareError> {
+    let (generated, generator) = time(|| generator(input));
+    let generated = generated.map_err(|error| CompareError::Generator(error.to_string()))?;
+
+    let variants = variants
+        .iter()
+        .take(limit)
+        .map(|&(name, solver)| {
+            let (answer, elapsed) = time(|| solver(&generated));
+            Timing { name, answer, elapsed }
+        })
+        .collect();
+
+    Ok(Comparison { day, part, generator, variants })
+}
+
+// runs every variant of the part on `input` once, timing each one
+pub fn run_variants(day: u32, part: u32, input: &[u8]) -> Result<Comparison, CompareError> {
+    run_registered(day, part, input, usize::MAX)
+}
+
+// like `run_variants` with only the default variant
+pub fn run_default(day: u32, part: u32, input: &[u8]) -> Result<Comparison, CompareError> {
+    run_registered(day, part, input, 1)
+}
+
+fn run_registered(day: u32, part: u32, input: &[u8], limit: usize) -> Result<Comparison, CompareError> {
+    let id = (day, part);
+    let raw = |input| Ok::<_, String>(input);
+
+    match id {
+        (1, 1) => run(id, input, raw, &[
+            ("default", |input| answer(day1::solver_part1(input))),
+            ("Swar", |input| answer(day1::solver_part1_swar(input))),
+        ], limit),
+        (1, 2) => run(id, input, raw, &[
+            ("default", |input| answer(day1::solver_part2(input))),
+            ("Scan", |input| answer(day1::solver_part2_scan(input))),
+        ], limit),
+        (2, 1) => run(id, input, day2::generator, &[
+            ("default", |games| answer(day2::part1_solver(games))),
+        ], limit),
+        (2, 2) => run(id, input, day2::generator, &[
+            ("default", |games| answer(day2::part2_solver(games))),
+        ], limit),
+        (3, 1) => run(id, input, day3::generator, &[
+            ("default", |schematic| answer(day3::solver_part1(schematic))),
+            ("Brute", |schematic| answer(day3::solver_part1_brute(schematic))),
+            ("Graph", |schematic| answer(day3::solver_part1_graph(schematic))),
+        ], limit),
+        (3, 2) => run(id, input, day3::generator, &[
+            ("default", |schematic| answer(day3::solver_part2(schematic))),
+            ("Brute", |schematic| answer(day3::solver_part2_brute(schematic))),
+            ("Graph", |schematic| answer(day3::solver_part2_graph(schematic))),
+        ], limit),
+        _ => Err(CompareError::UnknownPart { day, part }),
+    }
+}
+
+// like `run_variants`, but variants giving different answers are an error
+pub fn compare(day: u32, part: u32, input: &[u8]) -> Result<Comparison, CompareError> {
+    let comparison = run_variants(day, part, input)?;
+
+    if comparison.agree() {
+        Ok(comparison)
+    } else {
+        Err(CompareError::Disagreement(comparison))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, fs, path::Path};
+
+    use super::*;
+
+    // arguments of every `#[aoc(...)]` in `source`, however the attribute is
+    // formatted; comments are dropped first so commented-out ones don't count
+    fn aoc_attributes(source: &str) -> Vec<Vec<String>> {
+        let code = source
+            .lines()
+            .map(|line| line.split("//").next().unwrap())
+            .flat_map(str::chars)
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+
+        ["#[aoc(", "#[aoc_runner_derive::aoc("]
+            .into_iter()
+            .flat_map(|prefix| code.match_indices(prefix).map(move |(start, _)| start + prefix.len()))
+            .map(|start| {
+                let arguments = &code[start..start + code[start..].find(')').expect("unterminated #[aoc(...)]")];
+                arguments.split(',').filter(|argument| !argument.is_empty()).map(str::to_string).collect()
+            })
+            .collect()
+    }
+
+    // (day, part, variant) of every `#[aoc(...)]` in src/day*.rs
+    fn aoc_registrations() -> BTreeSet<(u32, u32, String)> {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut registrations = BTreeSet::new();
+
+        for entry in fs::read_dir(src).unwrap() {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            if !(name.starts_with("day") && name.ends_with(".rs")) {
+                continue;
+            }
+
+            for arguments in aoc_attributes(&fs::read_to_string(&path).unwrap()) {
+                let [day, part, variant @ ..] = &arguments[..] else {
+                    panic!("{name}: #[aoc({})] needs a day and a part", arguments.join(", "));
+                };
+                let number = |argument: &str, prefix| {
+                    argument
+                        .strip_prefix(prefix)
+                        .and_then(|number| number.parse().ok())
+                        .unwrap_or_else(|| panic!("{name}: expected {prefix}N, found {argument:?}"))
+                };
+                let (day, part) = (number(day, "day"), number(part, "part"));
+                let variant = variant.first().map_or("default", String::as_str).to_string();
+
+                registrations.insert((day, part, variant));
+            }
+        }
+
+        registrations
+    }
+
+    fn input(day: u32) -> &'static [u8] {
+        match day {
+            1 => include_bytes!("../input/2023/day1.txt"),
+            2 => include_bytes!("../input/2023/day2.txt"),
+            _ => include_bytes!("../input/2023/day3.txt"),
+        }
+    }
+
+    #[test]
+    fn variants_agree_on_real_inputs() {
+        for (day, part) in PARTS {
+            let comparison = compare(day, part, input(day)).unwrap();
+
+            assert!(comparison.answer().is_ok(), "day {day} part {part}");
+        }
+    }
+
+    #[test]
+    fn attributes_are_parsed_whatever_the_formatting() {
+        let source = "
+            #[aoc(day1, part1)]
+            fn a() {}
+            #[aoc(
+                day1,
+                part2,
+                Scan,
+            )]
+            fn b() {}
+            #[ aoc ( day3 , part1 , Brute ) ]
+            fn c() {}
+            #[aoc_runner_derive::aoc(day2, part2)]
+            fn d() {}
+            #[aoc_generator(day2)]
+            // #[aoc(day9, part1)]
+            fn e() {}
+        ";
+
+        let mut attributes = aoc_attributes(source);
+        attributes.sort();
+        assert_eq!(attributes, [
+            vec!["day1", "part1"],
+            vec!["day1", "part2", "Scan"],
+            vec!["day2", "part2"],
+            vec!["day3", "part1", "Brute"],
+        ]);
+    }
+
+    #[test]
+    fn table_matches_aoc_registrations() {
+        let registrations = aoc_registrations();
+        let parts = registrations.iter().map(|&(day, part, _)| (day, part)).collect::<BTreeSet<_>>();
+        assert_eq!(parts, PARTS.into_iter().collect(), "PARTS is out of date");
+
+        let table = PARTS
+            .into_iter()
+            .flat_map(|(day, part)| {
+                let comparison = run_variants(day, part, input(day)).unwrap();
+                comparison.variants.into_iter().map(move |timing| (day, part, timing.name.to_string()))
+            })
+            .collect::<BTreeSet<_>>();
+        assert_eq!(table, registrations, "run_registered is out of date");
+    }
+
+    #[test]
+    fn variants_agree_on_errors() {
+        // the error is the answer, and every variant reports the same one
+        let comparison = compare(1, 2, b"one\nzzz").unwrap();
+
+        assert_eq!(comparison.answer(), &Err("no digit on line 2".to_string()));
+        assert_eq!(comparison.variants.len(), 2);
+    }
+
+    #[test]
+    fn default_runs_only_the_first_variant() {
+        let comparison = run_default(3, 1, include_bytes!("../input/2023/example3.txt")).unwrap();
+
+        assert_eq!(comparison.variants.len(), 1);
+        assert_eq!(comparison.variants[0].name, "default");
+        assert_eq!(comparison.answer(), &Ok("4361".to_string()));
+    }
+
+    #[test]
+    fn unknown_parts_and_generator_failures() {
+        assert_eq!(compare(4, 1, b""), Err(CompareError::UnknownPart { day: 4, part: 1 }));
+        assert!(matches!(compare(2, 1, b"Game x"), Err(CompareError::Generator(_))));
+    }
+
+    #[test]
+    fn disagreement_is_reported() {
+        let comparison = run((9, 1), b"", Ok::<_, String>, &[
+            ("default", |_| Ok("1".to_string())),
+            ("Other", |_| Ok("2".to_string())),
+        ], usize::MAX)
+        .unwrap();
+
+        assert!(!comparison.agree());
+        assert!(comparison.to_string().contains("Other"));
+    }
+}
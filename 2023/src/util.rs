@@ -1,10 +1,57 @@
-use std::fmt::{Debug, Display};
+use std::{cmp::Ordering, error::Error, fmt::{Debug, Display}, ops::{Add, Mul, Sub}, str::FromStr};
 
-use num_traits::{Num, cast, NumCast};
+use num_traits::{Num, cast, NumCast, CheckedAdd, CheckedMul, CheckedSub, Zero, One};
+
+pub mod point_n;
+pub mod cycle;
+pub mod interval;
+pub mod math;
+pub mod parse;
+pub mod render;
+pub mod search;
+pub mod sparse_grid;
+pub mod stream;
+
+pub use point_n::PointN;
+pub use sparse_grid::SparseGrid;
 
 pub trait NumTraits = Num + NumCast + Default + Copy + Display;
 
-#[derive(Clone, Copy, PartialEq, PartialOrd, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowError;
+
+impl Display for OverflowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "arithmetic overflow while accumulating values")
+    }
+}
+
+impl Error for OverflowError {}
+
+// sums and products that widen each value into the accumulator type `A`
+// and report overflow instead of panicking or wrapping
+pub trait CheckedAccumulate: Iterator + Sized {
+    fn checked_sum<A>(mut self) -> Result<A, OverflowError>
+    where
+        A: Zero + CheckedAdd,
+        Self::Item: Into<A>,
+    {
+        self.try_fold(A::zero(), |acc, value| acc.checked_add(&value.into()).ok_or(OverflowError))
+    }
+
+    fn checked_product<A>(mut self) -> Result<A, OverflowError>
+    where
+        A: One + CheckedMul,
+        Self::Item: Into<A>,
+    {
+        self.try_fold(A::one(), |acc, value| acc.checked_mul(&value.into()).ok_or(OverflowError))
+    }
+}
+
+impl<I: Iterator> CheckedAccumulate for I {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point<T: NumTraits> {
     pub x: T,
     pub y: T,
@@ -15,23 +62,150 @@ impl<T: NumTraits> Point<T> {
         Self { x, y }
     }
 
-    pub fn dist_manhattan(self, other: Self) -> T {
-        (self.x - other.x) + (self.y - other.y) 
+    pub fn dist_manhattan(self, other: Self) -> T
+    where
+        T: PartialOrd,
+    {
+        PointN::from(self).dist_manhattan(other.into())
+    }
+
+    pub fn dist_euclidean(self, other: Self) -> f64
+    where
+        T: PartialOrd,
+    {
+        PointN::from(self).dist_euclidean(other.into())
+    }
+
+    pub fn axis_neighbours(self) -> impl Iterator<Item = Self>
+    where
+        T: CheckedAdd + CheckedSub,
+    {
+        PointN::from(self).axis_neighbours().map(Self::from)
+    }
+
+    pub fn all_neighbours(self) -> impl Iterator<Item = Self>
+    where
+        T: CheckedAdd + CheckedSub,
+    {
+        PointN::from(self).all_neighbours().map(Self::from)
+    }
+}
+
+impl<T: NumTraits> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: NumTraits> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: NumTraits> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self {
+        Self::new(self.x * scalar, self.y * scalar)
+    }
+}
+
+// row-major: points compare by row first, then by column
+impl<T: NumTraits + PartialOrd> PartialOrd for Point<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.y, self.x).partial_cmp(&(other.y, other.x))
+    }
+}
+
+impl<T: NumTraits + Ord> Ord for Point<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointCastError;
+
+impl Display for PointCastError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "coordinate is not exactly representable in the point's coordinate type")
+    }
+}
+
+impl Error for PointCastError {}
+
+// exact conversions only: out of range and fractional coordinates are
+// rejected, `cast` alone would truncate 1.5 to 1
+impl<S: NumCast + Copy + PartialEq, D: NumTraits> TryFrom<(S, S)> for Point<D> {
+    type Error = PointCastError;
+
+    fn try_from((x, y): (S, S)) -> Result<Self, Self::Error> {
+        let exact = |source: S| {
+            cast::<S, D>(source)
+                .filter(|&target| cast::<D, S>(target) == Some(source))
+                .ok_or(PointCastError)
+        };
+
+        Ok(Point { x: exact(x)?, y: exact(y)? })
     }
+}
 
-    pub fn dist_euclidean(self, other: Self) -> f64 {
-        let x = self.x - other.x;
-        let y = self.y - other.y;
-        let squared = x * x + y * y;
+// "x,y" without spaces, which is what `FromStr` reads back
+impl<T: NumTraits> Display for Point<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
 
-        cast::<T, f64>(squared).map(f64::sqrt).unwrap_or(0.0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParsePointError {
+    MissingSeparator,
+    InvalidCoordinate,
+}
+
+impl Display for ParsePointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingSeparator => write!(f, "expected a point written as \"x,y\""),
+            Self::InvalidCoordinate => write!(f, "invalid point coordinate"),
+        }
+    }
+}
+
+impl Error for ParsePointError {}
+
+impl<T: NumTraits> FromStr for Point<T> {
+    type Err = ParsePointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s.split_once(',').ok_or(ParsePointError::MissingSeparator)?;
+        let parse = |coordinate: &str| T::from_str_radix(coordinate.trim(), 10).map_err(|_| ParsePointError::InvalidCoordinate);
+
+        Ok(Point::new(parse(x)?, parse(y)?))
     }
 }
 
-impl<S: NumCast, D: NumTraits> From<(S, S)> for Point<D> {
-    fn from((x, y): (S, S)) -> Self {
-        Point {x: cast::<S, D>(x).unwrap_or_default(), y: cast::<S, D>(y).unwrap_or_default()}
+pub fn json_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
     }
+
+    escaped
 }
 
 impl<T: NumTraits> Debug for Point<T> {
@@ -39,3 +213,71 @@ impl<T: NumTraits> Debug for Point<T> {
         write!(f, "(x: {}, y: {})", self.x, self.y)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_orders_row_major() {
+        let mut points = vec![Point::new(2u16, 0), Point::new(0, 1), Point::new(1, 0)];
+        points.sort();
+
+        assert_eq!(points, vec![Point::new(1, 0), Point::new(2, 0), Point::new(0, 1)]);
+        assert!(Point::new(5.0, 0.0) < Point::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn point_neighbours() {
+        assert_eq!(Point::new(0u16, 0).axis_neighbours().count(), 2);
+        assert_eq!(Point::new(0u16, 0).all_neighbours().count(), 3);
+        assert_eq!(Point::new(5i64, 5).all_neighbours().count(), 8);
+        assert_eq!(Point::new(1i64, 2) + Point::new(3, 4) - Point::new(1, 1), Point::new(3, 5));
+    }
+
+    #[test]
+    fn point_as_key() {
+        let set = [Point::new(1i64, 2), Point::new(1, 2), Point::new(2, 1)]
+            .into_iter()
+            .collect::<std::collections::HashSet<_>>();
+
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn point_try_from_rejects_lossy_casts() {
+        assert_eq!(Point::<u16>::try_from((3usize, 4usize)), Ok(Point::new(3, 4)));
+        assert_eq!(Point::<u16>::try_from((70_000usize, 4usize)), Err(PointCastError));
+        assert_eq!(Point::<u8>::try_from((-1i32, 4i32)), Err(PointCastError));
+        assert_eq!(Point::<i32>::try_from((1.5f64, 2.9f64)), Err(PointCastError));
+        assert_eq!(Point::<i32>::try_from((1.0f64, -2.0f64)), Ok(Point::new(1, -2)));
+        assert_eq!(Point::<f32>::try_from((16_777_217i64, 0i64)), Err(PointCastError));
+    }
+
+    #[test]
+    fn point_display_round_trip() {
+        let point = Point::new(-12i32, 7);
+
+        assert_eq!(point.to_string(), "-12,7");
+        assert_eq!(point.to_string().parse(), Ok(point));
+        assert_eq!(" 3 , 4 ".parse(), Ok(Point::new(3u8, 4)));
+        assert_eq!("3;4".parse::<Point<u8>>(), Err(ParsePointError::MissingSeparator));
+        assert_eq!("3,x".parse::<Point<u8>>(), Err(ParsePointError::InvalidCoordinate));
+        assert_eq!("300,1".parse::<Point<u8>>(), Err(ParsePointError::InvalidCoordinate));
+    }
+
+    #[test]
+    fn checked_sum_widens() {
+        let values = [u16::MAX, u16::MAX, 2];
+
+        assert_eq!(values.into_iter().checked_sum::<u32>(), Ok(2 * u16::MAX as u32 + 2));
+        assert_eq!(values.into_iter().checked_sum::<u16>(), Err(OverflowError));
+    }
+
+    #[test]
+    fn checked_product_overflow() {
+        assert_eq!([1000u32, 1000, 1000].into_iter().checked_product::<u32>(), Ok(1_000_000_000));
+        assert_eq!([1000u32, 1000, 1000, 10].into_iter().checked_product::<u32>(), Err(OverflowError));
+        assert_eq!(std::iter::empty::<u8>().checked_product::<u64>(), Ok(1));
+    }
+}
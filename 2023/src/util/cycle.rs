@@ -0,0 +1,242 @@
+use std::{collections::HashMap, hash::Hash, rc::Rc};
+
+// the sequence x0, f(x0), f(f(x0)), ... enters a loop after `start` steps
+// and then repeats every `length` steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    // the earliest step whose state equals the state at step `n`
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+// Floyd's tortoise and hare, constant memory
+pub fn floyd<S: PartialEq + Clone>(initial: S, step: impl Fn(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+// Brent's algorithm, constant memory and fewer steps than Floyd
+pub fn brent<S: PartialEq + Clone>(initial: S, step: impl Fn(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+// every state up to the first repeat, so any later state can be read back
+// without simulating it
+#[derive(Debug, Clone)]
+pub struct StateCache<S> {
+    history: Vec<S>,
+    cycle: Cycle,
+}
+
+impl<S: Hash + Eq + Clone> StateCache<S> {
+    pub fn detect(initial: S, mut step: impl FnMut(&S) -> S) -> Self {
+        let mut seen = HashMap::from([(initial.clone(), 0)]);
+        let mut history = vec![initial];
+
+        loop {
+            let next = step(history.last().unwrap());
+
+            if let Some(&start) = seen.get(&next) {
+                let length = history.len() - start;
+                return Self { history, cycle: Cycle { start, length } };
+            }
+
+            seen.insert(next.clone(), history.len());
+            history.push(next);
+        }
+    }
+
+    pub fn cycle(&self) -> Cycle {
+        self.cycle
+    }
+
+    pub fn history(&self) -> &[S] {
+        &self.history
+    }
+
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.history[self.cycle.equivalent_step(n)]
+    }
+}
+
+// the state after `n` steps, stopping early once a repeat makes the rest
+// predictable
+pub fn state_at<S: Hash + Eq + Clone>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let mut seen = HashMap::from([(initial.clone(), 0)]);
+    let mut history = vec![initial];
+
+    while history.len() <= n {
+        let next = step(history.last().unwrap());
+
+        if let Some(&start) = seen.get(&next) {
+            let cycle = Cycle { start, length: history.len() - start };
+            return history.swap_remove(cycle.equivalent_step(n));
+        }
+
+        seen.insert(next.clone(), history.len());
+        history.push(next);
+    }
+
+    history.swap_remove(n)
+}
+
+type MemoFn<'a, K, V> = Rc<dyn Fn(&mut Memo<'a, K, V>, K) -> V + 'a>;
+
+// memoised recursive function, `f` recurses through the `Memo` it is given
+pub struct Memo<'a, K, V> {
+    cache: HashMap<K, V>,
+    f: MemoFn<'a, K, V>,
+}
+
+impl<'a, K: Hash + Eq + Clone, V: Clone> Memo<'a, K, V> {
+    pub fn new(f: impl Fn(&mut Memo<'a, K, V>, K) -> V + 'a) -> Self {
+        Self {
+            cache: HashMap::new(),
+            f: Rc::new(f),
+        }
+    }
+
+    pub fn get(&mut self, key: K) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            return value.clone();
+        }
+
+        let f = Rc::clone(&self.f);
+        let value = f(self, key.clone());
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn cached(&self) -> usize {
+        self.cache.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 3
+    fn tail_step(&n: &u32) -> u32 {
+        if n == 5 { 3 } else { n + 1 }
+    }
+
+    #[test]
+    fn detectors_agree() {
+        let expected = Cycle { start: 3, length: 3 };
+
+        assert_eq!(floyd(0, tail_step), expected);
+        assert_eq!(brent(0, tail_step), expected);
+        assert_eq!(StateCache::detect(0, tail_step).cycle(), expected);
+
+        let lcg = |&x: &u64| (x * 37 + 11) % 1000;
+        assert_eq!(floyd(7, lcg), brent(7, lcg));
+        assert_eq!(floyd(7, lcg), StateCache::detect(7, lcg).cycle());
+    }
+
+    #[test]
+    fn pure_cycle() {
+        let step = |&n: &u8| (n + 1) % 4;
+
+        assert_eq!(floyd(0, step), Cycle { start: 0, length: 4 });
+        assert_eq!(brent(0, step), Cycle { start: 0, length: 4 });
+    }
+
+    #[test]
+    fn extrapolation() {
+        let cache = StateCache::detect(0, tail_step);
+        let simulate = |n| (0..n).fold(0, |state, _| tail_step(&state));
+
+        for n in 0..50 {
+            assert_eq!(*cache.state_at(n), simulate(n));
+            assert_eq!(state_at(0, tail_step, n), simulate(n));
+        }
+
+        assert_eq!(state_at(0, tail_step, 1_000_000_000), *cache.state_at(1_000_000_000));
+    }
+
+    #[test]
+    fn memoised_recursion() {
+        let mut fibonacci = Memo::new(|memo, n: u64| if n < 2 { n } else { memo.get(n - 1) + memo.get(n - 2) });
+
+        assert_eq!(fibonacci.get(90), 2_880_067_194_370_816_120);
+        assert_eq!(fibonacci.cached(), 91);
+    }
+
+    #[test]
+    fn memo_with_captured_context() {
+        // paths from the top left to (x, y) moving right or down around walls
+        let walls = [(1, 1)];
+        let mut paths = Memo::new(|memo, (x, y): (u32, u32)| {
+            if walls.contains(&(x, y)) {
+                0u64
+            } else if x == 0 || y == 0 {
+                1
+            } else {
+                memo.get((x - 1, y)) + memo.get((x, y - 1))
+            }
+        });
+
+        assert_eq!(paths.get((2, 2)), 2);
+    }
+}
@@ -0,0 +1,293 @@
+use std::fmt::{Debug, Display};
+
+use super::NumTraits;
+
+// closed integer interval `start..=end`, never empty
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T: NumTraits> {
+    start: T,
+    end: T,
+}
+
+impl<T: NumTraits + Ord> Interval<T> {
+    // `None` when `start > end`
+    pub fn new(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    pub fn point(value: T) -> Self {
+        Self { start: value, end: value }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    pub fn len(&self) -> T {
+        self.end - self.start + T::one()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    // overlapping or directly next to each other, so the union is one interval
+    pub fn touches(&self, other: &Self) -> bool {
+        self.intersects(other)
+            || (self.end < other.start && self.end + T::one() == other.start)
+            || (other.end < self.start && other.end + T::one() == self.start)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    // `None` when the intervals neither overlap nor touch
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        self.touches(other).then(|| self.hull(other))
+    }
+
+    // smallest interval containing both
+    pub fn hull(&self, other: &Self) -> Self {
+        Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    // the parts of `self` below and above `other`
+    pub fn difference(&self, other: &Self) -> (Option<Self>, Option<Self>) {
+        let (below, _, above) = self.split(other);
+        (below, above)
+    }
+
+    // `self` cut into the parts below, inside and above `other`
+    pub fn split(&self, other: &Self) -> (Option<Self>, Option<Self>, Option<Self>) {
+        let below = if self.start < other.start {
+            Self::new(self.start, self.end.min(other.start - T::one()))
+        } else {
+            None
+        };
+
+        let above = if other.end < self.end {
+            Self::new(self.start.max(other.end + T::one()), self.end)
+        } else {
+            None
+        };
+
+        (below, self.intersection(other), above)
+    }
+
+    // grown by `by` on both sides without leaving `bounds`
+    pub fn expand_within(&self, by: T, bounds: &Self) -> Self {
+        let start = if self.start <= bounds.start || self.start - bounds.start < by {
+            bounds.start
+        } else {
+            self.start - by
+        };
+
+        let end = if bounds.end <= self.end || bounds.end - self.end < by {
+            bounds.end
+        } else {
+            self.end + by
+        };
+
+        Self { start, end }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + use<T> {
+        let (mut next, end) = (Some(self.start), self.end);
+
+        std::iter::from_fn(move || {
+            let value = next?;
+            next = (value < end).then(|| value + T::one());
+            Some(value)
+        })
+    }
+}
+
+impl<T: NumTraits> Debug for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..={}", self.start, self.end)
+    }
+}
+
+impl<T: NumTraits> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..={}", self.start, self.end)
+    }
+}
+
+// sorted, disjoint and non-touching intervals
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T: NumTraits> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: NumTraits + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { intervals: Vec::new() }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // number of values covered by the set
+    pub fn count(&self) -> T {
+        self.intervals.iter().fold(T::zero(), |acc, interval| acc + interval.len())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        // first interval that ends at or after `value`
+        let idx = self.intervals.partition_point(|interval| interval.end < value);
+        self.intervals.get(idx).is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut merged = interval;
+
+        // intervals touching `interval` form one contiguous run
+        let first = self.intervals.partition_point(|existing| existing.end < merged.start && !existing.touches(&merged));
+        let mut last = first;
+
+        while last < self.intervals.len() && self.intervals[last].touches(&merged) {
+            merged = merged.hull(&self.intervals[last]);
+            last += 1;
+        }
+
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval<T>) {
+        self.intervals = self.intervals
+            .iter()
+            .flat_map(|existing| {
+                let (below, above) = existing.difference(&interval);
+                below.into_iter().chain(above)
+            })
+            .collect();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        other.intervals.iter().for_each(|&interval| union.insert(interval));
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+
+            if let Some(overlap) = a.intersection(&b) {
+                intervals.push(overlap);
+            }
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        other.intervals.iter().for_each(|&interval| difference.remove(interval));
+        difference
+    }
+}
+
+impl<T: NumTraits + Ord> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|interval| set.insert(interval));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iv(start: i64, end: i64) -> Interval<i64> {
+        Interval::new(start, end).unwrap()
+    }
+
+    #[test]
+    fn basic_queries() {
+        assert_eq!(Interval::new(3, 2), None);
+        assert_eq!(iv(2, 5).len(), 4);
+        assert!(iv(2, 5).intersects(&iv(5, 9)));
+        assert!(!iv(2, 5).intersects(&iv(6, 9)));
+        assert!(iv(2, 5).touches(&iv(6, 9)));
+        assert_eq!(iv(2, 5).intersection(&iv(4, 9)), Some(iv(4, 5)));
+        assert_eq!(iv(2, 5).union(&iv(6, 9)), Some(iv(2, 9)));
+        assert_eq!(iv(2, 5).union(&iv(7, 9)), None);
+        assert_eq!(iv(-1, 2).iter().collect::<Vec<_>>(), vec![-1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn split_and_difference() {
+        assert_eq!(iv(0, 10).split(&iv(3, 5)), (Some(iv(0, 2)), Some(iv(3, 5)), Some(iv(6, 10))));
+        assert_eq!(iv(0, 10).split(&iv(-5, 5)), (None, Some(iv(0, 5)), Some(iv(6, 10))));
+        assert_eq!(iv(0, 10).split(&iv(20, 30)), (Some(iv(0, 10)), None, None));
+        assert_eq!(iv(0, 10).difference(&iv(0, 10)), (None, None));
+    }
+
+    #[test]
+    fn expand_within_clamps() {
+        let bounds = Interval::new(0u16, 9).unwrap();
+
+        assert_eq!(Interval::point(0u16).expand_within(1, &bounds), Interval::new(0, 1).unwrap());
+        assert_eq!(Interval::point(5u16).expand_within(1, &bounds), Interval::new(4, 6).unwrap());
+        assert_eq!(Interval::new(8u16, 9).unwrap().expand_within(3, &bounds), Interval::new(5, 9).unwrap());
+    }
+
+    #[test]
+    fn set_normalises() {
+        let set = [iv(10, 12), iv(1, 3), iv(4, 5), iv(8, 8), iv(2, 2)]
+            .into_iter()
+            .collect::<IntervalSet<_>>();
+
+        assert_eq!(set.intervals(), &[iv(1, 5), iv(8, 8), iv(10, 12)]);
+        assert_eq!(set.count(), 9);
+        assert!(set.contains(11) && !set.contains(9));
+
+        let mut bridged = set.clone();
+        bridged.insert(iv(6, 9));
+        assert_eq!(bridged.intervals(), &[iv(1, 12)]);
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a = [iv(0, 10), iv(20, 30)].into_iter().collect::<IntervalSet<_>>();
+        let b = [iv(5, 25)].into_iter().collect::<IntervalSet<_>>();
+
+        assert_eq!(a.union(&b).intervals(), &[iv(0, 30)]);
+        assert_eq!(a.intersection(&b).intervals(), &[iv(5, 10), iv(20, 25)]);
+        assert_eq!(a.difference(&b).intervals(), &[iv(0, 4), iv(26, 30)]);
+        assert_eq!(b.difference(&a).intervals(), &[iv(11, 19)]);
+    }
+}
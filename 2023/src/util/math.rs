@@ -0,0 +1,320 @@
+use num_traits::{cast, Signed};
+
+use super::NumTraits;
+
+// overflows for `T::MIN`
+fn abs<T: NumTraits + PartialOrd>(value: T) -> T {
+    if value < T::zero() { T::zero() - value } else { value }
+}
+
+// |value| as u128, which also holds the magnitude of `T::MIN`
+fn magnitude<T: NumTraits>(value: T) -> u128 {
+    match cast::<T, u128>(value) {
+        Some(value) => value,
+        None => cast::<T, i128>(value).expect("value does not fit in i128").unsigned_abs(),
+    }
+}
+
+fn gcd_wide(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+// computed on the magnitudes, so `T::MIN` works as long as the result fits:
+// gcd(MIN, 0) and gcd(MIN, MIN) are -MIN and panic
+pub fn gcd<T: NumTraits + PartialOrd>(a: T, b: T) -> T {
+    cast(gcd_wide(magnitude(a), magnitude(b))).expect("gcd does not fit in the type")
+}
+
+// panics if the result does not fit in `T`
+pub fn lcm<T: NumTraits + PartialOrd>(a: T, b: T) -> T {
+    if a == T::zero() || b == T::zero() {
+        return T::zero();
+    }
+
+    let (a, b) = (magnitude(a), magnitude(b));
+    (a / gcd_wide(a, b)).checked_mul(b).and_then(cast).expect("lcm does not fit in the type")
+}
+
+// (g, x, y) such that a * x + b * y = g = gcd(a, b)
+pub fn extended_gcd<T: NumTraits + Signed + PartialOrd>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (T::one(), T::zero());
+    let (mut old_t, mut t) = (T::zero(), T::one());
+
+    while r != T::zero() {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+        (old_t, t) = (t, old_t - quotient * t);
+    }
+
+    if old_r < T::zero() {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+// x in 0..modulus with a * x = 1 (mod modulus), `None` if a and modulus are
+// not coprime or modulus is 0
+pub fn mod_inverse<T: NumTraits + Signed + PartialOrd>(a: T, modulus: T) -> Option<T> {
+    if modulus == T::zero() {
+        return None;
+    }
+
+    let modulus = abs(modulus);
+    let (g, x, _) = extended_gcd(a, modulus);
+
+    (g == T::one()).then(|| ((x % modulus) + modulus) % modulus)
+}
+
+// the value reduced into 0..modulus, computed in u128 so that products of
+// two residues of any primitive integer modulus do not overflow
+fn residue<T: NumTraits>(value: T, modulus: u128) -> u128 {
+    match cast::<T, u128>(value) {
+        Some(value) => value % modulus,
+        None => {
+            let value = cast::<T, i128>(value).expect("value does not fit in i128");
+            value.rem_euclid(modulus as i128) as u128
+        },
+    }
+}
+
+// base^exponent mod modulus, modulus must be positive and exponent non-negative
+pub fn mod_pow<T: NumTraits + PartialOrd>(base: T, exponent: T, modulus: T) -> T {
+    assert!(modulus > T::zero(), "modulus must be positive");
+    assert!(exponent >= T::zero(), "exponent must be non-negative");
+
+    let modulus_wide = cast::<T, u128>(modulus).expect("modulus does not fit in u128");
+    let mut exponent = cast::<T, u128>(exponent).expect("exponent does not fit in u128");
+    let mut base = residue(base, modulus_wide);
+    let mut result = 1 % modulus_wide;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus_wide;
+        }
+        base = base * base % modulus_wide;
+        exponent >>= 1;
+    }
+
+    cast(result).unwrap()
+}
+
+// smallest non-negative x and the combined modulus such that x = r (mod m)
+// for every (r, m), moduli need not be coprime; `None` if a modulus is 0,
+// the congruences are inconsistent or the combined modulus overflows `T`
+pub fn crt<T: NumTraits + PartialOrd>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let (mut x, mut m) = (0i128, 1i128);
+
+    for &(residue, modulus) in congruences {
+        let modulus = cast::<T, i128>(modulus)?.checked_abs()?;
+        if modulus == 0 {
+            return None;
+        }
+        let residue = cast::<T, i128>(residue)?.rem_euclid(modulus);
+
+        // x + m * k = residue (mod modulus)
+        let (g, p, _) = extended_gcd(m, modulus);
+        let difference = residue - x;
+
+        if difference % g != 0 {
+            return None;
+        }
+
+        let step = modulus / g;
+        let k = (difference / g % step).checked_mul(p % step)?.rem_euclid(step);
+
+        x = x.checked_add(m.checked_mul(k)?)?;
+        m = m.checked_mul(step)?;
+        x = x.rem_euclid(m);
+
+        // every later step only grows m, so stop while it still fits in i128
+        cast::<i128, T>(m)?;
+    }
+
+    Some((cast(x)?, cast(m)?))
+}
+
+// floor(sqrt(n)), panics for negative n
+pub fn isqrt<T: NumTraits + PartialOrd>(n: T) -> T {
+    assert!(n >= T::zero(), "square root of a negative number");
+
+    let n = cast::<T, u128>(n).unwrap();
+    if n < 2 {
+        return cast(n).unwrap();
+    }
+
+    // Newton's method from above, starting at a power of two >= sqrt(n)
+    let mut x = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return cast(x).unwrap();
+        }
+        x = next;
+    }
+}
+
+// sieve of Eratosthenes over 0..=limit
+#[derive(Debug, Clone)]
+pub struct Sieve {
+    prime: Vec<bool>,
+}
+
+impl Sieve {
+    pub fn new(limit: usize) -> Self {
+        let mut prime = vec![true; limit + 1];
+        prime[0] = false;
+        if limit >= 1 {
+            prime[1] = false;
+        }
+
+        let mut p = 2;
+        while p * p <= limit {
+            if prime[p] {
+                (p * p..=limit).step_by(p).for_each(|multiple| prime[multiple] = false);
+            }
+            p += 1;
+        }
+
+        Self { prime }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.prime.len() - 1
+    }
+
+    // panics if n is above the sieve's limit
+    pub fn is_prime(&self, n: usize) -> bool {
+        self.prime[n]
+    }
+
+    pub fn primes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.prime.iter().enumerate().filter(|(_, &prime)| prime).map(|(n, _)| n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "gcd does not fit")]
+    fn gcd_of_min_and_zero_overflows() {
+        gcd(i32::MIN, 0);
+    }
+
+    #[test]
+    fn known_values() {
+        assert_eq!(gcd(12u32, 18), 6);
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(0u8, 0), 0);
+        assert_eq!(lcm(4i32, -6), 12);
+        assert_eq!(gcd(i64::MIN, 6), 2);
+        assert_eq!(gcd(i64::MAX, i64::MIN), 1);
+        assert_eq!(gcd(u128::MAX, 5), 5);
+        assert_eq!(lcm(i64::MIN / 2, 2), 1 << 62);
+        assert_eq!(lcm(i64::MIN, 0), 0);
+        assert_eq!(mod_inverse(3i64, 11), Some(4));
+        assert_eq!(mod_inverse(4i64, 8), None);
+        assert_eq!(mod_inverse(1i64, 0), None);
+        assert_eq!(mod_inverse(-1i64, 0), None);
+        assert_eq!(mod_pow(2u64, 10, 1000), 24);
+        assert_eq!(mod_pow(-2i64, 3, 5), 2);
+        assert_eq!(crt(&[(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1i64, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(3u64, 4), (1, 6)]), Some((7, 12)));
+        assert_eq!(crt(&[(2i64, 3), (1, 0)]), None);
+        assert_eq!(crt(&[(0u32, 0)]), None);
+        // the first two combine to about 2^126, which no longer fits u64
+        assert_eq!(crt(&[(1u64, (1 << 63) - 1), (2, (1 << 63) - 2), (0, 3)]), None);
+        assert_eq!(crt(&[(1u128, (1 << 100) - 1), (2, (1 << 100) - 2), (0, 3)]), None);
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        assert_eq!(Sieve::new(30).primes().collect::<Vec<_>>(), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    }
+
+    proptest! {
+        #[test]
+        fn gcd_lcm_relation(a in -10_000i64..10_000, b in -10_000i64..10_000) {
+            let g = gcd(a, b);
+
+            if g != 0 {
+                prop_assert_eq!(a % g, 0);
+                prop_assert_eq!(b % g, 0);
+                prop_assert_eq!(g * lcm(a, b), (a * b).abs());
+            }
+        }
+
+        #[test]
+        fn extended_gcd_identity(a in any::<i32>(), b in any::<i32>()) {
+            let (a, b) = (a as i64, b as i64);
+            let (g, x, y) = extended_gcd(a, b);
+
+            prop_assert_eq!(g, gcd(a, b));
+            prop_assert_eq!(a as i128 * x as i128 + b as i128 * y as i128, g as i128);
+        }
+
+        #[test]
+        fn inverse_multiplies_to_one(a in any::<i64>(), m in 2i64..1_000_000_007) {
+            match mod_inverse(a, m) {
+                Some(inverse) => prop_assert_eq!((a as i128 * inverse as i128).rem_euclid(m as i128), 1),
+                None => prop_assert_ne!(gcd(a, m), 1),
+            }
+        }
+
+        #[test]
+        fn pow_matches_repeated_multiplication(base in any::<u64>(), exponent in 0u64..64, modulus in 1u64..u64::MAX) {
+            let expected = (0..exponent).fold(1 % modulus as u128, |acc, _| acc * (base % modulus) as u128 % modulus as u128);
+            prop_assert_eq!(mod_pow(base, exponent, modulus) as u128, expected);
+        }
+
+        #[test]
+        fn crt_satisfies_congruences(congruences in prop::collection::vec((any::<i16>(), 1i64..50), 1..5)) {
+            let congruences = congruences.into_iter().map(|(r, m)| (r as i64, m)).collect::<Vec<_>>();
+            let brute = (0..congruences.iter().fold(1, |acc, &(_, m)| lcm(acc, m)))
+                .find(|x| congruences.iter().all(|&(r, m)| x % m == r.rem_euclid(m)));
+
+            prop_assert_eq!(crt(&congruences).map(|(x, _)| x), brute);
+        }
+
+        #[test]
+        fn crt_large_moduli(congruences in prop::collection::vec((any::<u64>(), (1u64 << 40)..=u64::MAX), 1..4)) {
+            match crt(&congruences) {
+                Some((x, m)) => {
+                    prop_assert!(x < m);
+                    for &(r, modulus) in &congruences {
+                        prop_assert_eq!(x % modulus, r % modulus);
+                    }
+                },
+                // inconsistent, or the combined modulus is past u64
+                None => prop_assert!(
+                    congruences.len() > 1
+                        || congruences.iter().any(|&(_, modulus)| modulus == 0)
+                ),
+            }
+        }
+
+        #[test]
+        fn isqrt_bounds(n in any::<u64>()) {
+            let root = isqrt(n) as u128;
+
+            prop_assert!(root * root <= n as u128);
+            prop_assert!((root + 1) * (root + 1) > n as u128);
+        }
+
+        #[test]
+        fn sieve_matches_trial_division(n in 0usize..2_000) {
+            let sieve = Sieve::new(2_000);
+            let trial = n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0);
+
+            prop_assert_eq!(sieve.is_prime(n), trial);
+        }
+    }
+}
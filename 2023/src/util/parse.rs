@@ -0,0 +1,260 @@
+use std::{error::Error, fmt::Display};
+
+use num_traits::{CheckedAdd, CheckedMul};
+
+use super::NumTraits;
+
+// parsers work on the remaining input and fail with the number of bytes
+// that were left, `parse_all` turns that into a position in the whole input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub remaining: usize,
+    pub expected: String,
+}
+
+pub type ParseResult<'a, T> = Result<(T, &'a [u8]), Failure>;
+
+fn fail<T>(input: &[u8], expected: impl Into<String>) -> ParseResult<'_, T> {
+    Err(Failure { remaining: input.len(), expected: expected.into() })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    // byte offset into the parsed input
+    pub position: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(position: usize, expected: impl Into<String>) -> Self {
+        Self { position, expected: expected.into() }
+    }
+
+    // 1-based line and column of `position` in `input`
+    pub fn line_col(&self, input: &[u8]) -> (usize, usize) {
+        let before = &input[..self.position.min(input.len())];
+        let line = before.iter().filter(|&&c| c == b'\n').count() + 1;
+        let column = before.iter().rev().take_while(|&&c| c != b'\n').count() + 1;
+
+        (line, column)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected {} at byte {}", self.expected, self.position)
+    }
+}
+
+impl Error for ParseError {}
+
+pub trait Parser<'a, T> = Fn(&'a [u8]) -> ParseResult<'a, T>;
+
+// runs `parser` over all of `input`, trailing bytes are an error
+pub fn parse_all<'a, T>(parser: impl Parser<'a, T>, input: &'a [u8]) -> Result<T, ParseError> {
+    let to_error = |remaining: usize, expected| ParseError::new(input.len() - remaining, expected);
+
+    match parser(input) {
+        Ok((value, [])) => Ok(value),
+        Ok((_, rest)) => Err(to_error(rest.len(), "end of input".to_string())),
+        Err(Failure { remaining, expected }) => Err(to_error(remaining, expected)),
+    }
+}
+
+pub fn tag<'a>(expected: &'static [u8]) -> impl Parser<'a, &'a [u8]> {
+    move |input: &'a [u8]| match input.strip_prefix(expected) {
+        Some(rest) => Ok((&input[..expected.len()], rest)),
+        None => fail(input, format!("{:?}", String::from_utf8_lossy(expected))),
+    }
+}
+
+// a single byte matching `predicate`
+pub fn satisfy<'a>(description: &'static str, predicate: impl Fn(u8) -> bool) -> impl Parser<'a, u8> {
+    move |input: &'a [u8]| match input.split_first() {
+        Some((&c, rest)) if predicate(c) => Ok((c, rest)),
+        _ => fail(input, description),
+    }
+}
+
+// the longest non-empty prefix of bytes matching `predicate`
+pub fn take_while1<'a>(description: &'static str, predicate: impl Fn(u8) -> bool) -> impl Parser<'a, &'a [u8]> {
+    move |input: &'a [u8]| {
+        let length = input.iter().take_while(|&&c| predicate(c)).count();
+
+        if length == 0 {
+            fail(input, description)
+        } else {
+            Ok(input.split_at(length))
+        }
+    }
+}
+
+// decimal digits, overflowing `T` is an error rather than a wrapped value
+pub fn uint<'a, T: NumTraits + CheckedMul + CheckedAdd>() -> impl Parser<'a, T> {
+    move |input: &'a [u8]| {
+        let (digits, rest) = take_while1("digit", |c| c.is_ascii_digit())(input)?;
+        let ten = T::from(10).unwrap();
+
+        let value = digits.iter().try_fold(T::zero(), |acc, &digit| {
+            acc.checked_mul(&ten)?.checked_add(&T::from(digit - b'0')?)
+        });
+
+        match value {
+            Some(value) => Ok((value, rest)),
+            None => fail(input, "number in range"),
+        }
+    }
+}
+
+pub fn map<'a, A, B>(parser: impl Parser<'a, A>, f: impl Fn(A) -> B) -> impl Parser<'a, B> {
+    move |input: &'a [u8]| parser(input).map(|(value, rest)| (f(value), rest))
+}
+
+// like `map` but `f` may reject the value, reported at the start of it
+pub fn map_res<'a, A, B>(parser: impl Parser<'a, A>, expected: &'static str, f: impl Fn(A) -> Option<B>) -> impl Parser<'a, B> {
+    move |input: &'a [u8]| {
+        let (value, rest) = parser(input)?;
+        match f(value) {
+            Some(value) => Ok((value, rest)),
+            None => fail(input, expected),
+        }
+    }
+}
+
+pub fn pair<'a, A, B>(first: impl Parser<'a, A>, second: impl Parser<'a, B>) -> impl Parser<'a, (A, B)> {
+    move |input: &'a [u8]| {
+        let (a, rest) = first(input)?;
+        let (b, rest) = second(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+pub fn preceded<'a, A, B>(prefix: impl Parser<'a, A>, parser: impl Parser<'a, B>) -> impl Parser<'a, B> {
+    map(pair(prefix, parser), |(_, b)| b)
+}
+
+pub fn terminated<'a, A, B>(parser: impl Parser<'a, A>, suffix: impl Parser<'a, B>) -> impl Parser<'a, A> {
+    map(pair(parser, suffix), |(a, _)| a)
+}
+
+pub fn delimited<'a, A, B, C>(open: impl Parser<'a, A>, parser: impl Parser<'a, B>, close: impl Parser<'a, C>) -> impl Parser<'a, B> {
+    preceded(open, terminated(parser, close))
+}
+
+// tries `first`, then `second`; on double failure the error that got
+// furthest into the input wins
+pub fn alt<'a, T>(first: impl Parser<'a, T>, second: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    move |input: &'a [u8]| match first(input) {
+        Ok(result) => Ok(result),
+        Err(a) => match second(input) {
+            Ok(result) => Ok(result),
+            Err(b) if a.remaining == b.remaining => Err(Failure {
+                remaining: a.remaining,
+                expected: format!("{} or {}", a.expected, b.expected),
+            }),
+            Err(b) => Err(if a.remaining < b.remaining { a } else { b }),
+        },
+    }
+}
+
+pub fn opt<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Option<T>> {
+    move |input: &'a [u8]| match parser(input) {
+        Ok((value, rest)) => Ok((Some(value), rest)),
+        Err(_) => Ok((None, input)),
+    }
+}
+
+// one or more repetitions
+pub fn many1<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a [u8]| {
+        let (first, mut rest) = parser(input)?;
+        let mut values = vec![first];
+
+        while let Ok((value, next)) = parser(rest) {
+            // a parser that consumes nothing would repeat forever
+            if next.len() == rest.len() {
+                break;
+            }
+            values.push(value);
+            rest = next;
+        }
+
+        Ok((values, rest))
+    }
+}
+
+// one or more `item`s separated by `separator`; an item that fails on its
+// first byte ends the list before the separator, one that fails part way
+// through is an error
+pub fn sep_by1<'a, T, S>(item: impl Parser<'a, T>, separator: impl Parser<'a, S>) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a [u8]| {
+        let (first, mut rest) = item(input)?;
+        let mut values = vec![first];
+
+        while let Ok((_, after_separator)) = separator(rest) {
+            match item(after_separator) {
+                Ok((value, next)) => {
+                    values.push(value);
+                    rest = next;
+                },
+                Err(failure) if failure.remaining == after_separator.len() => break,
+                Err(failure) => return Err(failure),
+            }
+        }
+
+        Ok((values, rest))
+    }
+}
+
+// zero or more `item`s separated by `separator`
+pub fn sep_by<'a, T, S>(item: impl Parser<'a, T>, separator: impl Parser<'a, S>) -> impl Parser<'a, Vec<T>> {
+    let items = sep_by1(item, separator);
+
+    move |input: &'a [u8]| match items(input) {
+        Err(failure) if failure.remaining == input.len() => Ok((Vec::new(), input)),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitives() {
+        assert_eq!(tag(b"red")(b"red, 1"), Ok((&b"red"[..], &b", 1"[..])));
+        assert!(tag(b"red")(b"blue").is_err());
+        assert_eq!(uint::<u32>()(b"123abc"), Ok((123, &b"abc"[..])));
+        assert!(uint::<u8>()(b"256").is_err());
+        assert!(uint::<u8>()(b"x").is_err());
+    }
+
+    #[test]
+    fn combinators() {
+        let list = delimited(tag(b"["), sep_by(uint::<u16>(), tag(b",")), tag(b"]"));
+
+        assert_eq!(parse_all(&list, b"[1,2,3]"), Ok(vec![1, 2, 3]));
+        assert_eq!(parse_all(&list, b"[]"), Ok(vec![]));
+        assert_eq!(parse_all(&list, b"[1,2,]"), Err(ParseError::new(4, r#""]""#)));
+        assert_eq!(parse_all(&list, b"[1,2]x"), Err(ParseError::new(5, "end of input")));
+
+        let colour = alt(map(tag(b"red"), |_| 'r'), map(tag(b"blue"), |_| 'b'));
+        assert_eq!(parse_all(&colour, b"blue"), Ok('b'));
+        assert_eq!(parse_all(&colour, b"green"), Err(ParseError::new(0, r#""red" or "blue""#)));
+    }
+
+    #[test]
+    fn alt_reports_furthest_failure() {
+        let parser = alt(pair(tag(b"ab"), tag(b"c")), pair(tag(b"a"), tag(b"d")));
+
+        assert_eq!(parse_all(parser, b"abx"), Err(ParseError::new(2, r#""c""#)));
+    }
+
+    #[test]
+    fn error_line_col() {
+        let error = ParseError::new(6, "digit");
+
+        assert_eq!(error.line_col(b"ab\ncdef"), (2, 4));
+        assert_eq!(error.to_string(), "expected digit at byte 6");
+    }
+}
@@ -0,0 +1,266 @@
+use std::{cmp::Ordering, fmt::{Debug, Display}, ops::{Add, Index, IndexMut, Mul, Sub}};
+
+use num_traits::{cast, CheckedAdd, CheckedSub, Zero};
+
+use super::{NumTraits, Point};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PointN<T: NumTraits, const N: usize> {
+    pub coords: [T; N],
+}
+
+// |a - b| without requiring a signed type
+fn abs_diff<T: NumTraits + PartialOrd>(a: T, b: T) -> T {
+    if a < b { b - a } else { a - b }
+}
+
+impl<T: NumTraits, const N: usize> PointN<T, N> {
+    pub const fn new(coords: [T; N]) -> Self {
+        Self { coords }
+    }
+
+    pub fn origin() -> Self {
+        Self { coords: [T::zero(); N] }
+    }
+
+    pub fn dist_manhattan(self, other: Self) -> T
+    where
+        T: PartialOrd,
+    {
+        self.coords.iter()
+            .zip(other.coords)
+            .fold(T::zero(), |acc, (&a, b)| acc + abs_diff(a, b))
+    }
+
+    pub fn dist_euclidean(self, other: Self) -> f64
+    where
+        T: PartialOrd,
+    {
+        let squared = self.coords.iter()
+            .zip(other.coords)
+            .map(|(&a, b)| abs_diff(a, b))
+            .fold(T::zero(), |acc, d| acc + d * d);
+
+        cast::<T, f64>(squared).map(f64::sqrt).unwrap_or(0.0)
+    }
+
+    // the point moved by -1, 0 or +1 along each axis, `None` if a coordinate
+    // leaves the range of `T`
+    fn offset(self, offsets: [i8; N]) -> Option<Self>
+    where
+        T: CheckedAdd + CheckedSub,
+    {
+        let mut coords = self.coords;
+
+        for (coord, offset) in coords.iter_mut().zip(offsets) {
+            *coord = match offset {
+                -1 => coord.checked_sub(&T::one())?,
+                1 => coord.checked_add(&T::one())?,
+                _ => *coord,
+            };
+        }
+
+        Some(Self { coords })
+    }
+
+    // the 2N neighbours that differ in exactly one coordinate
+    pub fn axis_neighbours(self) -> impl Iterator<Item = Self>
+    where
+        T: CheckedAdd + CheckedSub,
+    {
+        (0..N).flat_map(move |axis| {
+            [-1, 1].into_iter().filter_map(move |direction| {
+                let mut offsets = [0; N];
+                offsets[axis] = direction;
+                self.offset(offsets)
+            })
+        })
+    }
+
+    // all 3^N - 1 neighbours, diagonals included
+    pub fn all_neighbours(self) -> impl Iterator<Item = Self>
+    where
+        T: CheckedAdd + CheckedSub,
+    {
+        (0..3usize.pow(N as u32)).filter_map(move |mut combination| {
+            let mut offsets = [0; N];
+
+            for offset in offsets.iter_mut() {
+                *offset = (combination % 3) as i8 - 1;
+                combination /= 3;
+            }
+
+            if offsets.iter().all(|&offset| offset == 0) {
+                None
+            } else {
+                self.offset(offsets)
+            }
+        })
+    }
+}
+
+impl<T: NumTraits, const N: usize> Default for PointN<T, N> {
+    fn default() -> Self {
+        Self::origin()
+    }
+}
+
+impl<T: NumTraits, const N: usize> Add for PointN<T, N> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self.coords.iter_mut().zip(other.coords).for_each(|(a, b)| *a = *a + b);
+        self
+    }
+}
+
+impl<T: NumTraits, const N: usize> Sub for PointN<T, N> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self.coords.iter_mut().zip(other.coords).for_each(|(a, b)| *a = *a - b);
+        self
+    }
+}
+
+impl<T: NumTraits, const N: usize> Mul<T> for PointN<T, N> {
+    type Output = Self;
+
+    fn mul(mut self, scalar: T) -> Self {
+        self.coords.iter_mut().for_each(|a| *a = *a * scalar);
+        self
+    }
+}
+
+impl<T: NumTraits, const N: usize> Index<usize> for PointN<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &T {
+        &self.coords[axis]
+    }
+}
+
+impl<T: NumTraits, const N: usize> IndexMut<usize> for PointN<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut T {
+        &mut self.coords[axis]
+    }
+}
+
+// compares the last axis first so that N = 2 orders row-major like `Point`
+impl<T: NumTraits + PartialOrd, const N: usize> PartialOrd for PointN<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.coords.iter().rev().partial_cmp(other.coords.iter().rev())
+    }
+}
+
+impl<T: NumTraits + Ord, const N: usize> Ord for PointN<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.coords.iter().rev().cmp(other.coords.iter().rev())
+    }
+}
+
+impl<T: NumTraits> From<Point<T>> for PointN<T, 2> {
+    fn from(Point { x, y }: Point<T>) -> Self {
+        Self::new([x, y])
+    }
+}
+
+impl<T: NumTraits> From<PointN<T, 2>> for Point<T> {
+    fn from(PointN { coords: [x, y] }: PointN<T, 2>) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T: NumTraits, const N: usize> From<[T; N]> for PointN<T, N> {
+    fn from(coords: [T; N]) -> Self {
+        Self::new(coords)
+    }
+}
+
+impl<T: NumTraits, const N: usize> Debug for PointN<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(")?;
+        for (idx, coord) in self.coords.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{coord}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<T: NumTraits, const N: usize> Display for PointN<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, coord) in self.coords.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{coord}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: NumTraits, const N: usize> Zero for PointN<T, N> {
+    fn zero() -> Self {
+        Self::origin()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.coords.iter().all(T::is_zero)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_match_point() {
+        let (a, b) = (Point::new(1u32, 7), Point::new(4u32, 3));
+        let (na, nb) = (PointN::from(a), PointN::from(b));
+
+        assert_eq!(na.dist_manhattan(nb), 7);
+        assert_eq!(a.dist_manhattan(b), 7);
+        assert_eq!(na.dist_euclidean(nb), 5.0);
+        assert_eq!(a.dist_euclidean(b), 5.0);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = PointN::new([1i64, 2, 3]);
+        let b = PointN::new([4, 5, 6]);
+
+        assert_eq!(a + b, PointN::new([5, 7, 9]));
+        assert_eq!(b - a, PointN::new([3, 3, 3]));
+        assert_eq!(a * 2, PointN::new([2, 4, 6]));
+        assert_eq!(a[2], 3);
+    }
+
+    #[test]
+    fn neighbour_counts() {
+        let centre = PointN::new([0i32, 0, 0, 0]);
+
+        assert_eq!(centre.axis_neighbours().count(), 8);
+        assert_eq!(centre.all_neighbours().count(), 80);
+        assert!(centre.all_neighbours().all(|n| n != centre));
+        assert!(centre.axis_neighbours().all(|n| n.dist_manhattan(centre) == 1));
+    }
+
+    #[test]
+    fn neighbours_stay_in_range() {
+        let corner = PointN::new([0u8, 0, 0]);
+
+        assert_eq!(corner.axis_neighbours().count(), 3);
+        assert_eq!(corner.all_neighbours().count(), 7);
+    }
+
+    #[test]
+    fn orders_like_point() {
+        let mut points = [PointN::new([2u16, 0]), PointN::new([0, 1]), PointN::new([1, 0])];
+        points.sort();
+
+        assert_eq!(points.map(Point::from), [Point::new(1, 0), Point::new(2, 0), Point::new(0, 1)]);
+    }
+}
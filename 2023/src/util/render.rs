@@ -0,0 +1,156 @@
+use std::{fmt::Write, io, path::Path};
+
+use num_traits::cast;
+
+use super::{NumTraits, Point};
+
+const DEFAULT_CELL_SIZE: usize = 16;
+
+// cell based SVG canvas, coordinates are grid cells and areas are inclusive
+// on both corners like the areas used by the day solutions
+pub struct Svg {
+    width: usize,
+    height: usize,
+    cell_size: usize,
+    body: String,
+}
+
+fn to_cell<T: NumTraits>(point: Point<T>) -> (usize, usize) {
+    (cast(point.x).unwrap_or_default(), cast(point.y).unwrap_or_default())
+}
+
+fn escape_xml(text: &str) -> String {
+    text.chars().fold(String::with_capacity(text.len()), |mut escaped, c| {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+        escaped
+    })
+}
+
+impl Svg {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cell_size: DEFAULT_CELL_SIZE,
+            body: String::new(),
+        }
+    }
+
+    pub fn with_cell_size(mut self, cell_size: usize) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    pub fn fill_area<T: NumTraits>(&mut self, (tl, br): (Point<T>, Point<T>), colour: &str, opacity: f64) -> &mut Self {
+        let (x0, y0) = to_cell(tl);
+        let (x1, y1) = to_cell(br);
+        let size = self.cell_size;
+
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="{}"/>"#,
+            x0 * size,
+            y0 * size,
+            (x1.saturating_sub(x0) + 1) * size,
+            (y1.saturating_sub(y0) + 1) * size,
+            escape_xml(colour),
+            opacity
+        );
+
+        self
+    }
+
+    pub fn fill_cell<T: NumTraits>(&mut self, point: Point<T>, colour: &str) -> &mut Self {
+        self.fill_area((point, point), colour, 1.0)
+    }
+
+    pub fn outline_area<T: NumTraits>(&mut self, (tl, br): (Point<T>, Point<T>), colour: &str) -> &mut Self {
+        let (x0, y0) = to_cell(tl);
+        let (x1, y1) = to_cell(br);
+        let size = self.cell_size;
+
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="{}" stroke-width="1"/>"#,
+            x0 * size,
+            y0 * size,
+            (x1.saturating_sub(x0) + 1) * size,
+            (y1.saturating_sub(y0) + 1) * size,
+            escape_xml(colour)
+        );
+
+        self
+    }
+
+    // writes `text` one character per cell starting at `start`
+    pub fn text<T: NumTraits>(&mut self, start: Point<T>, text: &str, colour: &str) -> &mut Self {
+        let (x, y) = to_cell(start);
+        let size = self.cell_size;
+
+        for (offset, c) in text.chars().enumerate() {
+            let _ = writeln!(
+                self.body,
+                r#"<text x="{}" y="{}" fill="{}">{}</text>"#,
+                (x + offset) * size + size / 2,
+                y * size + size / 2,
+                escape_xml(colour),
+                escape_xml(&c.to_string())
+            );
+        }
+
+        self
+    }
+
+    pub fn finish(&self) -> String {
+        let (width, height) = (self.width * self.cell_size, self.height * self.cell_size);
+
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" "#,
+                r#"font-family="monospace" font-size="{f}" text-anchor="middle" dominant-baseline="central">"#,
+                "\n",
+                r#"<rect width="{w}" height="{h}" fill="white"/>"#,
+                "\n{body}</svg>\n"
+            ),
+            w = width,
+            h = height,
+            f = self.cell_size * 3 / 4,
+            body = self.body
+        )
+    }
+
+    pub fn write_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn area_covers_inclusive_corners() {
+        let mut svg = Svg::new(4, 3).with_cell_size(10);
+        svg.fill_area((Point::new(1u16, 0), Point::new(2, 1)), "red", 0.5);
+
+        let output = svg.finish();
+        assert!(output.contains(r#"width="40" height="30""#));
+        assert!(output.contains(r#"<rect x="10" y="0" width="20" height="20" fill="red" fill-opacity="0.5"/>"#));
+    }
+
+    #[test]
+    fn text_is_escaped() {
+        let mut svg = Svg::new(2, 1).with_cell_size(10);
+        svg.text(Point::new(0u16, 0), "<&", "black");
+
+        let output = svg.finish();
+        assert!(output.contains(r#"<text x="5" y="5" fill="black">&lt;</text>"#));
+        assert!(output.contains(r#"<text x="15" y="5" fill="black">&amp;</text>"#));
+    }
+}
@@ -0,0 +1,309 @@
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap, HashSet, VecDeque}, hash::Hash};
+
+use num_traits::{cast, CheckedAdd, CheckedSub};
+
+use super::{NumTraits, Point};
+
+// successors of a search state together with the cost of moving to them
+pub trait Neighbours<S, C> {
+    fn neighbours(&self, state: &S) -> impl IntoIterator<Item = (S, C)>;
+}
+
+impl<S, C, I, F> Neighbours<S, C> for F
+where
+    F: Fn(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    fn neighbours(&self, state: &S) -> impl IntoIterator<Item = (S, C)> {
+        self(state)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchResult<S, C> {
+    // start to goal inclusive, `None` if no goal was reached
+    pub path: Option<Vec<S>>,
+    // best known distance from the start to every discovered state
+    pub distances: HashMap<S, C>,
+    // states that were expanded
+    pub visited: HashSet<S>,
+}
+
+impl<S: Clone + Eq + Hash, C> SearchResult<S, C> {
+    fn new(start: S, zero: C) -> Self {
+        Self {
+            path: None,
+            distances: HashMap::from([(start, zero)]),
+            visited: HashSet::new(),
+        }
+    }
+
+    pub fn goal(&self) -> Option<&S> {
+        self.path.as_ref().and_then(|path| path.last())
+    }
+
+    pub fn goal_distance(&self) -> Option<&C> {
+        self.goal().and_then(|goal| self.distances.get(goal))
+    }
+}
+
+fn reconstruct<S: Clone + Eq + Hash>(previous: &HashMap<S, S>, goal: S) -> Vec<S> {
+    let mut path = vec![goal];
+
+    while let Some(prev) = previous.get(path.last().unwrap()) {
+        path.push(prev.clone());
+    }
+
+    path.reverse();
+    path
+}
+
+// unweighted search, distances count edges and edge costs are ignored
+pub fn bfs<S, C, N>(start: S, neighbours: &N, is_goal: impl Fn(&S) -> bool) -> SearchResult<S, usize>
+where
+    S: Clone + Eq + Hash,
+    N: Neighbours<S, C>,
+{
+    let mut result = SearchResult::new(start.clone(), 0);
+    let mut previous = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(state) = queue.pop_front() {
+        result.visited.insert(state.clone());
+
+        if is_goal(&state) {
+            result.path = Some(reconstruct(&previous, state));
+            break;
+        }
+
+        let distance = result.distances[&state] + 1;
+
+        for (next, _) in neighbours.neighbours(&state) {
+            if !result.distances.contains_key(&next) {
+                result.distances.insert(next.clone(), distance);
+                previous.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    result
+}
+
+pub fn dijkstra<S, C, N>(start: S, neighbours: &N, is_goal: impl Fn(&S) -> bool) -> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: NumTraits + Ord,
+    N: Neighbours<S, C>,
+{
+    astar(start, neighbours, is_goal, |_| C::zero())
+}
+
+// `heuristic` must never overestimate the remaining cost for the path to be
+// the cheapest one; it need not be consistent, an expanded state is expanded
+// again when a cheaper path to it turns up
+pub fn astar<S, C, N>(start: S, neighbours: &N, is_goal: impl Fn(&S) -> bool, heuristic: impl Fn(&S) -> C) -> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: NumTraits + Ord,
+    N: Neighbours<S, C>,
+{
+    let mut result = SearchResult::new(start.clone(), C::zero());
+    let mut previous = HashMap::new();
+
+    // heap entries index into `states`, the sequence number breaks ties in
+    // insertion order without requiring `S: Ord`
+    let mut states = vec![start.clone()];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::zero(), 0usize))]);
+
+    while let Some(Reverse((_, cost, idx))) = heap.pop() {
+        let state = states[idx].clone();
+
+        // stale entry for a state that was reached more cheaply since
+        if result.distances[&state] < cost {
+            continue;
+        }
+
+        result.visited.insert(state.clone());
+
+        if is_goal(&state) {
+            result.path = Some(reconstruct(&previous, state));
+            break;
+        }
+
+        for (next, step) in neighbours.neighbours(&state) {
+            let next_cost = cost + step;
+
+            if result.distances.get(&next).is_none_or(|&known| next_cost < known) {
+                result.distances.insert(next.clone(), next_cost);
+                previous.insert(next.clone(), state.clone());
+                heap.push(Reverse((next_cost + heuristic(&next), next_cost, states.len())));
+                states.push(next);
+            }
+        }
+    }
+
+    result
+}
+
+pub fn manhattan_to<T: NumTraits + PartialOrd>(goal: Point<T>) -> impl Fn(&Point<T>) -> T {
+    move |point| point.dist_manhattan(goal)
+}
+
+// rounded down so that the heuristic stays admissible in integer costs
+pub fn euclidean_to<T: NumTraits + PartialOrd>(goal: Point<T>) -> impl Fn(&Point<T>) -> T {
+    move |point| cast(point.dist_euclidean(goal).floor()).unwrap_or_default()
+}
+
+// rectangular grid of points where `enter_cost` gives the cost of stepping
+// onto a cell, or `None` when the cell is a wall
+pub struct PointGrid<T: NumTraits, F> {
+    // upper left, lower right (inclusive)
+    bounds: (Point<T>, Point<T>),
+    enter_cost: F,
+    diagonal: bool,
+}
+
+impl<T: NumTraits, F> PointGrid<T, F> {
+    pub fn new(bounds: (Point<T>, Point<T>), enter_cost: F) -> Self {
+        Self {
+            bounds,
+            enter_cost,
+            diagonal: false,
+        }
+    }
+
+    pub fn with_diagonals(mut self) -> Self {
+        self.diagonal = true;
+        self
+    }
+}
+
+impl<T, C, F> Neighbours<Point<T>, C> for PointGrid<T, F>
+where
+    T: NumTraits + PartialOrd + CheckedAdd + CheckedSub,
+    F: Fn(Point<T>) -> Option<C>,
+{
+    fn neighbours(&self, state: &Point<T>) -> impl IntoIterator<Item = (Point<T>, C)> {
+        let (tl, br) = self.bounds;
+        let (state, diagonal) = (*state, self.diagonal);
+
+        state.all_neighbours()
+            .filter(move |p| diagonal || p.x == state.x || p.y == state.y)
+            .filter(move |p| tl.x <= p.x && p.x <= br.x && tl.y <= p.y && p.y <= br.y)
+            .filter_map(|p| (self.enter_cost)(p).map(|cost| (p, cost)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: [&str; 5] = [
+        "S.#....",
+        ".##.##.",
+        "...#...",
+        ".#...#.",
+        "...#..G",
+    ];
+
+    fn maze() -> PointGrid<i64, impl Fn(Point<i64>) -> Option<i64>> {
+        PointGrid::new((Point::new(0, 0), Point::new(6, 4)), |p: Point<i64>| {
+            (MAZE[p.y as usize].as_bytes()[p.x as usize] != b'#').then_some(1)
+        })
+    }
+
+    fn is_goal(p: &Point<i64>) -> bool {
+        *p == Point::new(6, 4)
+    }
+
+    fn assert_valid_path(path: &[Point<i64>]) {
+        assert_eq!(path.first(), Some(&Point::new(0, 0)));
+        assert!(is_goal(path.last().unwrap()));
+        assert!(path.windows(2).all(|w| w[0].dist_manhattan(w[1]) == 1));
+    }
+
+    #[test]
+    fn bfs_shortest_path() {
+        let result = bfs(Point::new(0, 0), &maze(), is_goal);
+        let path = result.path.as_ref().unwrap();
+
+        assert_valid_path(path);
+        assert_eq!(path.len(), 11);
+        assert_eq!(result.goal_distance(), Some(&10));
+    }
+
+    #[test]
+    fn searches_agree() {
+        let grid = maze();
+        let goal = Point::new(6, 4);
+
+        let dijkstra = dijkstra(Point::new(0, 0), &grid, is_goal);
+        let manhattan = astar(Point::new(0, 0), &grid, is_goal, manhattan_to(goal));
+        let euclidean = astar(Point::new(0, 0), &grid, is_goal, euclidean_to(goal));
+
+        for result in [&dijkstra, &manhattan, &euclidean] {
+            assert_valid_path(result.path.as_ref().unwrap());
+            assert_eq!(result.goal_distance(), Some(&10));
+        }
+
+        assert!(manhattan.visited.len() <= dijkstra.visited.len());
+    }
+
+    #[test]
+    fn inconsistent_heuristic() {
+        // S-A 1, S-B 1, A-C 1, B-C 2, C-G 3; h(A) = 4 is exact but h(S) = 0
+        // is not consistent with it, so C is first expanded via B for 3
+        // before A offers it for 2
+        let edges = [('S', 'A', 1), ('S', 'B', 1), ('A', 'C', 1), ('B', 'C', 2), ('C', 'G', 3)];
+        let neighbours = |state: &char| {
+            edges.iter().filter(|edge| edge.0 == *state).map(|&(_, to, cost)| (to, cost)).collect::<Vec<_>>()
+        };
+        let heuristic = |state: &char| if *state == 'A' { 4 } else { 0 };
+
+        let result = astar('S', &neighbours, |state| *state == 'G', heuristic);
+
+        assert_eq!(result.path, Some(vec!['S', 'A', 'C', 'G']));
+        assert_eq!(result.goal_distance(), Some(&5));
+    }
+
+    #[test]
+    fn weighted_costs() {
+        // going straight through the middle row costs 9 per step
+        let grid = PointGrid::new((Point::new(0, 0), Point::new(2, 2)), |p: Point<i64>| {
+            Some(if p.y == 1 && p.x == 1 { 9 } else { 1 })
+        });
+
+        let result = dijkstra(Point::new(0, 1), &grid, |p| *p == Point::new(2, 1));
+
+        assert_eq!(result.goal_distance(), Some(&4));
+        assert!(!result.path.unwrap().contains(&Point::new(1, 1)));
+    }
+
+    #[test]
+    fn unreachable_goal_explores_everything() {
+        let grid = PointGrid::new((Point::new(0u8, 0), Point::new(3, 0)), |p: Point<u8>| (p.x != 2).then_some(1u8));
+        let result = dijkstra(Point::new(0, 0), &grid, |p| p.x == 3);
+
+        assert!(result.path.is_none());
+        assert_eq!(result.visited.len(), 2);
+        assert_eq!(result.distances.get(&Point::new(1, 0)), Some(&1));
+    }
+
+    #[test]
+    fn closure_neighbours() {
+        // states are integers, each step either adds one or doubles
+        let step = |&n: &u32| [(n + 1, 1u32), (n * 2, 1)];
+        let result = bfs(1, &step, |&n| n == 10);
+
+        assert_eq!(result.path, Some(vec![1, 2, 4, 5, 10]));
+    }
+
+    #[test]
+    fn diagonal_grid() {
+        let grid = PointGrid::new((Point::new(0, 0), Point::new(3, 3)), |_| Some(1)).with_diagonals();
+        let result = bfs(Point::new(0i64, 0), &grid, |p| *p == Point::new(3, 3));
+
+        assert_eq!(result.goal_distance(), Some(&3));
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use super::Point;
+
+type Cell = Point<i64>;
+
+// unbounded grid that only stores occupied cells, the bounding box is kept
+// up to date as cells are inserted and removed
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Cell, T>,
+    // upper left, lower right (inclusive)
+    bounds: Option<(Cell, Cell)>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn bounds(&self) -> Option<(Cell, Cell)> {
+        self.bounds
+    }
+
+    pub fn width(&self) -> usize {
+        self.bounds.map_or(0, |(tl, br)| (br.x - tl.x + 1) as usize)
+    }
+
+    pub fn height(&self) -> usize {
+        self.bounds.map_or(0, |(tl, br)| (br.y - tl.y + 1) as usize)
+    }
+
+    pub fn insert(&mut self, point: Cell, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => (point, point),
+            Some((tl, br)) => (
+                Point::new(tl.x.min(point.x), tl.y.min(point.y)),
+                Point::new(br.x.max(point.x), br.y.max(point.y)),
+            ),
+        });
+
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: Cell) -> Option<T> {
+        let value = self.cells.remove(&point)?;
+
+        // only a cell on the edge of the box can shrink it
+        if let Some((tl, br)) = self.bounds {
+            if point.x == tl.x || point.x == br.x || point.y == tl.y || point.y == br.y {
+                self.recalculate_bounds();
+            }
+        }
+
+        Some(value)
+    }
+
+    fn recalculate_bounds(&mut self) {
+        self.bounds = self.cells.keys().fold(None, |bounds, &point| {
+            Some(match bounds {
+                None => (point, point),
+                Some((tl, br)) => (
+                    Point::new(tl.x.min(point.x), tl.y.min(point.y)),
+                    Point::new(br.x.max(point.x), br.y.max(point.y)),
+                ),
+            })
+        });
+    }
+
+    pub fn get(&self, point: Cell) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Cell) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    pub fn contains(&self, point: Cell) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Cell, &T)> {
+        self.cells.iter().map(|(&point, value)| (point, value))
+    }
+
+    // occupied cells among the 4 axis-aligned neighbours
+    pub fn axis_neighbours(&self, point: Cell) -> impl Iterator<Item = (Cell, &T)> {
+        point.axis_neighbours().filter_map(|n| self.cells.get(&n).map(|value| (n, value)))
+    }
+
+    // occupied cells among the 8 surrounding neighbours
+    pub fn neighbours(&self, point: Cell) -> impl Iterator<Item = (Cell, &T)> {
+        point.all_neighbours().filter_map(|n| self.cells.get(&n).map(|value| (n, value)))
+    }
+
+    // one line per row of the bounding box, `to_char` receives `None` for
+    // unoccupied cells
+    pub fn render(&self, to_char: impl Fn(Option<&T>) -> char) -> String {
+        let Some((tl, br)) = self.bounds else {
+            return String::new();
+        };
+
+        let mut output = String::with_capacity((self.width() + 1) * self.height());
+
+        for y in tl.y..=br.y {
+            for x in tl.x..=br.x {
+                output.push(to_char(self.cells.get(&Point::new(x, y))));
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+
+    // rows of a dense grid placed with their first cell at the origin, cells
+    // for which `keep` is false are left out
+    pub fn from_rows<R: IntoIterator<Item = T>>(rows: impl IntoIterator<Item = R>, keep: impl Fn(&T) -> bool) -> Self {
+        let mut grid = Self::new();
+
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, value) in row.into_iter().enumerate() {
+                if keep(&value) {
+                    grid.insert(Point::new(x as i64, y as i64), value);
+                }
+            }
+        }
+
+        grid
+    }
+
+    // dense rows covering the bounding box, unoccupied cells set to `empty`;
+    // the first cell of the first row is the upper left corner of `bounds`
+    pub fn to_dense(&self, empty: T) -> Vec<Vec<T>>
+    where
+        T: Clone,
+    {
+        let Some((tl, br)) = self.bounds else {
+            return Vec::new();
+        };
+
+        (tl.y..=br.y)
+            .map(|y| {
+                (tl.x..=br.x)
+                    .map(|x| self.cells.get(&Point::new(x, y)).cloned().unwrap_or_else(|| empty.clone()))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(Cell, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Cell, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        iter.into_iter().for_each(|(point, value)| {
+            grid.insert(point, value);
+        });
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_follow_changes() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert(Point::new(0, 0), 'a');
+        grid.insert(Point::new(-3, 2), 'b');
+        grid.insert(Point::new(4, -1), 'c');
+        assert_eq!(grid.bounds(), Some((Point::new(-3, -1), Point::new(4, 2))));
+        assert_eq!((grid.width(), grid.height()), (8, 4));
+
+        grid.remove(Point::new(-3, 2));
+        assert_eq!(grid.bounds(), Some((Point::new(0, -1), Point::new(4, 0))));
+
+        grid.remove(Point::new(0, 0));
+        grid.remove(Point::new(4, -1));
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = SparseGrid::from_rows(["#.#", ".#.", "##."].map(str::chars), |&c| c == '#');
+
+        assert_eq!(grid.neighbours(Point::new(1, 1)).count(), 4);
+        assert_eq!(grid.axis_neighbours(Point::new(1, 1)).count(), 1);
+        assert_eq!(grid.neighbours(Point::new(-1, -1)).count(), 1);
+    }
+
+    #[test]
+    fn dense_round_trip() {
+        let rows = ["..#", "#..", ".#."];
+        let grid = SparseGrid::from_rows(rows.map(str::chars), |&c| c != '.');
+
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.render(|cell| cell.copied().unwrap_or('.')), "..#\n#..\n.#.\n");
+
+        let dense = grid.to_dense('.');
+        assert_eq!(dense, rows.map(|row| row.chars().collect::<Vec<_>>()));
+    }
+
+    #[test]
+    fn render_negative_coordinates() {
+        let grid = [(Point::new(-2, -1), 1), (Point::new(0, 0), 2)]
+            .into_iter()
+            .collect::<SparseGrid<u8>>();
+
+        assert_eq!(grid.render(|cell| cell.map_or(' ', |&v| (b'0' + v) as char)), "1  \n  2\n");
+    }
+
+    #[test]
+    fn reexported_from_util() {
+        // the path callers use, `aoc::util::SparseGrid`
+        let grid = crate::util::SparseGrid::from_rows(["#."].map(str::chars), |&c| c == '#');
+
+        assert_eq!(grid.get(Point::new(0, 0)), Some(&'#'));
+    }
+}
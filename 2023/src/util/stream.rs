@@ -0,0 +1,127 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    io::{self, BufRead, Read},
+};
+
+// failure of a solver reading its input incrementally: either the reader
+// or the input itself
+#[derive(Debug)]
+pub enum StreamError<E> {
+    Io(io::Error),
+    Input(E),
+}
+
+impl<E: Display> Display for StreamError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to read input: {error}"),
+            Self::Input(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for StreamError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Input(error) => Some(error),
+        }
+    }
+}
+
+impl<E> From<io::Error> for StreamError<E> {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+// calls `f` with the byte offset and contents of every line, without the
+// '\n'; one buffer is reused, so memory is bounded by the longest line
+pub fn for_each_line<R: BufRead, E>(
+    mut reader: R,
+    mut f: impl FnMut(usize, &[u8]) -> Result<(), E>,
+) -> Result<(), StreamError<E>> {
+    let mut buffer = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        buffer.clear();
+        let read = reader.read_until(b'\n', &mut buffer)?;
+        if read == 0 {
+            return Ok(());
+        }
+
+        let line = buffer.strip_suffix(b"\n").unwrap_or(&buffer);
+        f(offset, line).map_err(StreamError::Input)?;
+        offset += read;
+    }
+}
+
+// `template` repeated until `length` bytes have been produced, for feeding
+// the streaming solvers inputs far larger than memory
+pub struct Repeat<'a> {
+    template: &'a [u8],
+    position: usize,
+    remaining: u64,
+}
+
+impl<'a> Repeat<'a> {
+    pub fn new(template: &'a [u8], length: u64) -> Self {
+        assert!(!template.is_empty() || length == 0, "cannot repeat an empty template");
+
+        Self { template, position: 0, remaining: length }
+    }
+}
+
+impl Read for Repeat<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = &self.template[self.position..];
+        let count = buf.len().min(available.len()).min(self.remaining.try_into().unwrap_or(usize::MAX));
+
+        buf[..count].copy_from_slice(&available[..count]);
+        self.position = (self.position + count) % self.template.len().max(1);
+        self.remaining -= count as u64;
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use super::*;
+
+    #[test]
+    fn lines_and_offsets() {
+        let mut lines = Vec::new();
+        for_each_line::<_, ()>(&b"ab\n\ncd"[..], |offset, line| {
+            lines.push((offset, line.to_vec()));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(lines, vec![(0, b"ab".to_vec()), (3, b"".to_vec()), (4, b"cd".to_vec())]);
+    }
+
+    #[test]
+    fn stops_at_first_error() {
+        let mut seen = 0;
+        let result = for_each_line(&b"1\n2\n3\n"[..], |_, line| {
+            seen += 1;
+            if line == b"2" { Err("two") } else { Ok(()) }
+        });
+
+        assert!(matches!(result, Err(StreamError::Input("two"))));
+        assert_eq!(seen, 2);
+    }
+
+    #[test]
+    fn repeat_cuts_off_at_length() {
+        let mut output = String::new();
+        BufReader::with_capacity(4, Repeat::new(b"abc\n", 10)).read_to_string(&mut output).unwrap();
+
+        assert_eq!(output, "abc\nabc\nab");
+    }
+}
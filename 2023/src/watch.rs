@@ -0,0 +1,272 @@
+// Polling file watcher and run-to-run comparison behind `runner watch`.
+
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::runner::{self, Answer, CompareError, PARTS};
+
+// modification times of a fixed set of files; a file appearing or
+// disappearing counts as a change
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+impl Watcher {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let files = paths.into_iter().map(|path| {
+            let time = modified(&path);
+            (path, time)
+        });
+
+        Self { files: files.collect() }
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|(path, _)| path.as_path())
+    }
+
+    // files changed since the last call
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        self.files
+            .iter_mut()
+            .filter_map(|(path, time)| {
+                let now = modified(path);
+                (now != *time).then(|| {
+                    *time = now;
+                    path.clone()
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartRun {
+    // name of the input, e.g. "example3"
+    pub input: String,
+    pub part: u32,
+    pub answer: Answer,
+    pub generator: Duration,
+    pub solver: Duration,
+}
+
+// the default variant of every part of a day on every input
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Run {
+    pub parts: Vec<PartRun>,
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{duration:.1?}")
+}
+
+// signed difference, e.g. "+1.2ms"
+fn format_delta(now: Duration, before: Duration) -> String {
+    if now >= before {
+        format!("+{}", format_duration(now - before))
+    } else {
+        format!("-{}", format_duration(before - now))
+    }
+}
+
+fn format_answer(answer: &Answer) -> String {
+    match answer {
+        Ok(answer) => answer.clone(),
+        Err(error) => format!("error: {error}"),
+    }
+}
+
+impl Run {
+    pub fn execute(day: u32, inputs: &[(String, Vec<u8>)]) -> Self {
+        let parts = inputs
+            .iter()
+            .flat_map(|(name, input)| {
+                PARTS.iter().filter(move |&&(d, _)| d == day).map(move |&(_, part)| {
+                    let (answer, generator, solver) = match runner::run_default(day, part, input) {
+                        Ok(comparison) => {
+                            let timing = &comparison.variants[0];
+                            (timing.answer.clone(), comparison.generator, timing.elapsed)
+                        },
+                        Err(CompareError::Generator(error)) => (Err(format!("generator: {error}")), Duration::ZERO, Duration::ZERO),
+                        Err(error) => (Err(error.to_string()), Duration::ZERO, Duration::ZERO),
+                    };
+
+                    PartRun { input: name.clone(), part, answer, generator, solver }
+                })
+            })
+            .collect();
+
+        Self { parts }
+    }
+
+    fn find(&self, input: &str, part: u32) -> Option<&PartRun> {
+        self.parts.iter().find(|run| run.input == input && run.part == part)
+    }
+
+    // one line per part: the answer and timings, and how both moved since
+    // `previous`
+    pub fn report(&self, previous: Option<&Run>) -> String {
+        let input_width = self.parts.iter().map(|run| run.input.len()).max().unwrap_or(0);
+        let mut report = String::new();
+
+        for run in &self.parts {
+            let before = previous.and_then(|previous| previous.find(&run.input, run.part));
+            let answer = format_answer(&run.answer);
+
+            let answer = match before {
+                Some(before) if before.answer != run.answer => format!("{answer}  (was {})", format_answer(&before.answer)),
+                Some(_) => answer,
+                None if previous.is_some() => format!("{answer}  (new)"),
+                None => answer,
+            };
+            let timing = match before {
+                Some(before) => format!(
+                    "{} ({}) + {} ({})",
+                    format_duration(run.generator),
+                    format_delta(run.generator, before.generator),
+                    format_duration(run.solver),
+                    format_delta(run.solver, before.solver),
+                ),
+                None => format!("{} + {}", format_duration(run.generator), format_duration(run.solver)),
+            };
+
+            writeln!(report, "{:input_width$} part {}  {timing}  {answer}", run.input, run.part).unwrap();
+        }
+
+        report
+    }
+
+    // tab separated, one part per line, for handing a run to a rebuilt runner
+    pub fn to_text(&self) -> String {
+        let clean = |text: &str| text.replace(['\t', '\n'], " ");
+
+        self.parts
+            .iter()
+            .map(|run| {
+                let (status, answer) = match &run.answer {
+                    Ok(answer) => ("ok", clean(answer)),
+                    Err(error) => ("err", clean(error)),
+                };
+                format!("{}\t{}\t{status}\t{answer}\t{}\t{}\n", run.input, run.part, run.generator.as_nanos(), run.solver.as_nanos())
+            })
+            .collect()
+    }
+
+    // `None` if any line is malformed
+    pub fn from_text(text: &str) -> Option<Self> {
+        let parts = text
+            .lines()
+            .map(|line| {
+                let [input, part, status, answer, generator, solver] = line.split('\t').collect::<Vec<_>>()[..] else {
+                    return None;
+                };
+                let answer = match status {
+                    "ok" => Ok(answer.to_string()),
+                    "err" => Err(answer.to_string()),
+                    _ => return None,
+                };
+
+                Some(PartRun {
+                    input: input.to_string(),
+                    part: part.parse().ok()?,
+                    answer,
+                    generator: Duration::from_nanos(generator.parse().ok()?),
+                    solver: Duration::from_nanos(solver.parse().ok()?),
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self { parts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, process};
+
+    use super::*;
+
+    const EXAMPLE: &[u8] = include_bytes!("../input/2023/example3.txt");
+
+    fn part(input: &str, part: u32, answer: Answer, millis: u64) -> PartRun {
+        PartRun {
+            input: input.to_string(),
+            part,
+            answer,
+            generator: Duration::from_millis(millis),
+            solver: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn watcher_sees_changes_creation_and_removal() {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (existing, missing) = (dir.join("existing.txt"), dir.join("missing.txt"));
+        fs::write(&existing, "a").unwrap();
+
+        let mut watcher = Watcher::new([existing.clone(), missing.clone()]);
+        assert!(watcher.changed().is_empty());
+
+        // set the time explicitly, a rewrite can land in the same tick
+        let file = File::options().write(true).open(&existing).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+        assert_eq!(watcher.changed(), vec![existing.clone()]);
+        assert!(watcher.changed().is_empty());
+
+        fs::write(&missing, "b").unwrap();
+        assert_eq!(watcher.changed(), vec![missing.clone()]);
+        fs::remove_file(&missing).unwrap();
+        assert_eq!(watcher.changed(), vec![missing]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn runs_every_part_of_every_input() {
+        let run = Run::execute(3, &[("example3".to_string(), EXAMPLE.to_vec()), ("broken".to_string(), b"1\n12".to_vec())]);
+
+        let answers = run.parts.iter().map(|run| (run.input.as_str(), run.part, run.answer.clone())).collect::<Vec<_>>();
+        assert_eq!(answers[..2], [("example3", 1, Ok("4361".to_string())), ("example3", 2, Ok("467835".to_string()))]);
+        assert!(answers[2].2.as_ref().is_err_and(|error| error.starts_with("generator: ")));
+    }
+
+    #[test]
+    fn report_marks_changes() {
+        let before = Run { parts: vec![part("day1", 1, Ok("10".to_string()), 3), part("day1", 2, Ok("20".to_string()), 3)] };
+        let after = Run {
+            parts: vec![
+                part("day1", 1, Ok("10".to_string()), 1),
+                part("day1", 2, Err("no digit on line 2".to_string()), 5),
+                part("example1", 1, Ok("142".to_string()), 1),
+            ],
+        };
+
+        let report = after.report(Some(&before));
+        let lines = report.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "day1     part 1  1.0ms (-2.0ms) + 1.0ms (-2.0ms)  10");
+        assert_eq!(lines[1], "day1     part 2  5.0ms (+2.0ms) + 5.0ms (+2.0ms)  error: no digit on line 2  (was 20)");
+        assert_eq!(lines[2], "example1 part 1  1.0ms + 1.0ms  142  (new)");
+
+        assert_eq!(before.report(None).lines().next(), Some("day1 part 1  3.0ms + 3.0ms  10"));
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let run = Run { parts: vec![part("day2", 1, Ok("8".to_string()), 2), part("day2", 2, Err("bad\tinput".to_string()), 0)] };
+        let restored = Run::from_text(&run.to_text()).unwrap();
+
+        assert_eq!(restored.parts[0], run.parts[0]);
+        assert_eq!(restored.parts[1].answer, Err("bad input".to_string()));
+        assert_eq!(Run::from_text("day2\t1\tok\n"), None);
+    }
+}